@@ -1,7 +1,15 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use dbtective_core::DbtProjectAnalyzer;
+use dbtective_core::inventory::{write_inventory, InventoryFormat};
+use dbtective_core::parser::cache::ParseCache;
+use dbtective_core::parser::project::parse_cli_vars;
+use dbtective_core::types::severity::RuleSeverity;
+use dbtective_core::{
+    AnalyzerBuilder, Config, DbtectiveError, GitSource, Selection, TarballSource,
+};
+use std::path::Path;
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "dbtective")]
@@ -16,44 +24,228 @@ struct Cli {
 enum Commands {
     /// Check for dbt metadata issues
     Detect {
+        /// Project directory, or a .tar / .tar.gz archive of one
         #[arg(short, long, default_value = ".")]
         project_directory: String,
+
+        /// Parse every file instead of reusing results from `.dbtective/cache`
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Analyze the project as of a git revision (e.g. `origin/main`) instead of the working tree
+        #[arg(long)]
+        rev: Option<String>,
+
+        /// Vars overriding those in dbt_project.yml, as a YAML mapping like `{key: value}`
+        #[arg(long)]
+        vars: Option<String>,
+
+        /// Only check resources matching these dbt selectors, e.g. `tag:finance +orders`
+        #[arg(short, long, num_args = 1..)]
+        select: Vec<String>,
+
+        /// Skip resources matching these dbt selectors
+        #[arg(long, num_args = 1..)]
+        exclude: Vec<String>,
+
+        /// Only check resources picked by a named selector from selectors.yml
+        #[arg(long, conflicts_with = "select")]
+        selector: Option<String>,
+
+        /// Config file to use instead of the project's dbtective.toml or pyproject.toml
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+    /// Dump every parsed resource with its merged config
+    Inventory {
+        /// Project directory, or a .tar / .tar.gz archive of one
+        #[arg(short, long, default_value = ".")]
+        project_directory: String,
+
+        /// Output format: json, yaml or csv
+        #[arg(short, long, default_value = "json", value_parser = parse_inventory_format)]
+        format: InventoryFormat,
+
+        /// Parse every file instead of reusing results from `.dbtective/cache`
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Analyze the project as of a git revision (e.g. `origin/main`) instead of the working tree
+        #[arg(long)]
+        rev: Option<String>,
+
+        /// Vars overriding those in dbt_project.yml, as a YAML mapping like `{key: value}`
+        #[arg(long)]
+        vars: Option<String>,
+    },
+    /// Manage the incremental parse cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
     },
 }
 
-fn main() -> Result<()> {
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Remove the parse cache of a project
+    Clean {
+        #[arg(short, long, default_value = ".")]
+        project_directory: String,
+    },
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{} {}", "❌ Error:".bright_red(), error);
+            let code = error.downcast_ref::<DbtectiveError>().map_or(1, exit_code);
+            ExitCode::from(code)
+        }
+    }
+}
+
+/// Exit codes per error kind, so scripts can tell failures apart.
+/// 2 is left to clap for invalid arguments.
+fn exit_code(error: &DbtectiveError) -> u8 {
+    match error {
+        DbtectiveError::ProjectNotFound { .. } => 3,
+        DbtectiveError::Config { .. } => 4,
+        DbtectiveError::Yaml { .. } => 5,
+        DbtectiveError::Io { .. } => 6,
+        DbtectiveError::Rule { .. } => 7,
+        DbtectiveError::Output { .. } => 8,
+        DbtectiveError::Git { .. } => 9,
+    }
+}
+
+/// Analyze a project directory, an archive when given a file, or the
+/// project as of a git revision
+fn analyzer(project_directory: &str, rev: Option<&str>) -> Result<AnalyzerBuilder> {
+    let path = Path::new(project_directory);
+    if let Some(rev) = rev {
+        Ok(AnalyzerBuilder::from_source(GitSource::open(path, rev)?))
+    } else if path.is_file() {
+        Ok(AnalyzerBuilder::from_source(TarballSource::open(path)?))
+    } else {
+        Ok(AnalyzerBuilder::new(path))
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Some(Commands::Detect { project_directory }) => {
+        Some(Commands::Detect {
+            project_directory,
+            no_cache,
+            rev,
+            vars,
+            select,
+            exclude,
+            selector,
+            config,
+        }) => {
             println!("\n{}\n", "( •_•)>⌐■-■   dbt-tective".bright_cyan());
             // Use the core analyzer
-            let analyzer = DbtProjectAnalyzer::new(project_directory.clone());
-            match analyzer.analyze() {
-                Ok(report) => {
-                    println!(
-                        "📊 Analysis complete for: {}",
-                        report.project_path.bright_yellow()
-                    );
-                    println!(
-                        "🎯 Overall Score: {:.1}%",
-                        report.overall_score.to_string().bright_green()
-                    );
+            let vars = vars.as_deref().map(parse_cli_vars).transpose()?;
+            let mut builder = analyzer(&project_directory, rev.as_deref())?;
+            if let Some(config) = config {
+                builder = builder.with_config(Config::load(Path::new(&config))?);
+            }
+            let report = builder
+                .with_cache(!no_cache)
+                .with_vars(vars.unwrap_or_default())
+                .with_selection(Selection {
+                    select,
+                    exclude,
+                    selector,
+                })
+                .build()
+                .analyze()?;
+            println!(
+                "📊 Analysis complete for: {}",
+                report.project_path.bright_yellow()
+            );
+            println!("🗂️  Resources parsed: {}", report.resource_count);
+            println!(
+                "🎯 Overall Score: {}%",
+                format!("{:.1}", report.overall_score).bright_green()
+            );
+            if report.suppressed > 0 {
+                println!("🔕 Findings suppressed: {}", report.suppressed);
+            }
 
-                    if !report.findings.is_empty() {
-                        println!("\n🔍 Findings:");
-                        for finding in &report.findings {
-                            println!("  • {}", finding);
-                        }
-                    }
+            if !report.diagnostics.is_empty() {
+                println!("\n⚠️  Diagnostics:");
+                for diagnostic in &report.diagnostics {
+                    println!("  • {}", diagnostic.to_string().yellow());
                 }
-                Err(e) => {
-                    println!("{} {}", "❌ Analysis failed:".bright_red(), e);
+            }
+
+            if !report.unused_suppressions.is_empty() {
+                println!("\n🧹 Unused suppressions:");
+                for suppression in &report.unused_suppressions {
+                    println!("  • {}", suppression.to_string().yellow());
+                }
+            }
+
+            if !report.findings.is_empty() {
+                println!("\n🔍 Findings:");
+                for finding in &report.findings {
+                    let severity = match finding.severity {
+                        RuleSeverity::Error => finding.severity.as_str().bright_red(),
+                        RuleSeverity::Warning => finding.severity.as_str().yellow(),
+                        RuleSeverity::Info => finding.severity.as_str().bright_blue(),
+                    };
+                    println!(
+                        "  • [{}] {} {}: {} ({})",
+                        severity,
+                        finding.rule_id,
+                        finding.resource_name.bright_yellow(),
+                        finding.message,
+                        finding.file_path
+                    );
                 }
             }
 
             println!("\n{}\n", "(⌐■_■)        Case solved!".bright_cyan());
         }
+        Some(Commands::Inventory {
+            project_directory,
+            format,
+            no_cache,
+            rev,
+            vars,
+        }) => {
+            let vars = vars.as_deref().map(parse_cli_vars).transpose()?;
+            let parsed = analyzer(&project_directory, rev.as_deref())?
+                .with_cache(!no_cache)
+                .with_vars(vars.unwrap_or_default())
+                .build()
+                .parse()?;
+
+            // Keep stdout clean for the inventory itself
+            for diagnostic in &parsed.diagnostics {
+                eprintln!("{} {}", "⚠️ ".yellow(), diagnostic.to_string().yellow());
+            }
+            write_inventory(&parsed.project, format, std::io::stdout().lock())?;
+        }
+        Some(Commands::Cache {
+            command: CacheCommands::Clean { project_directory },
+        }) => {
+            if ParseCache::clean(Path::new(&project_directory))? {
+                println!(
+                    "🧹 Removed parse cache for: {}",
+                    project_directory.bright_yellow()
+                );
+            } else {
+                println!(
+                    "No parse cache found for: {}",
+                    project_directory.bright_yellow()
+                );
+            }
+        }
         None => {
             println!("\n{}", "Use --help for available commands.".bright_yellow());
         }
@@ -61,3 +253,8 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn parse_inventory_format(format: &str) -> Result<InventoryFormat, String> {
+    InventoryFormat::from_str(format)
+        .ok_or_else(|| format!("unknown format '{}', expected json, yaml or csv", format))
+}
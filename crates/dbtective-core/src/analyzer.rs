@@ -0,0 +1,444 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::parser::{DbtProjectParser, ParseResult};
+use crate::rules::{builtin_rules, engine, Rule};
+use crate::scoring;
+use crate::selector::{NamedSelectors, Selection};
+#[cfg(feature = "fs")]
+use crate::source::DirectorySource;
+use crate::source::FileSource;
+use crate::types::dbt::{DbtProject, DbtResource, ResourceType};
+use crate::types::diagnostic::Diagnostic;
+use crate::types::finding::Finding;
+use crate::types::severity::RuleSeverity;
+use crate::types::suppression::Suppression;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::PathBuf;
+
+type ResourcePredicate = Box<dyn Fn(&dyn DbtResource) -> bool + Send + Sync>;
+
+/// Limits the resources rules are run against
+pub enum ResourceFilter {
+    /// Only resources of these types
+    ResourceTypes(Vec<ResourceType>),
+    /// Only resources the predicate accepts
+    Custom(ResourcePredicate),
+}
+
+impl ResourceFilter {
+    pub fn custom(predicate: impl Fn(&dyn DbtResource) -> bool + Send + Sync + 'static) -> Self {
+        Self::Custom(Box::new(predicate))
+    }
+
+    pub fn matches(&self, resource: &dyn DbtResource) -> bool {
+        match self {
+            Self::ResourceTypes(types) => types.contains(&resource.resource_type()),
+            Self::Custom(predicate) => predicate(resource),
+        }
+    }
+}
+
+/// What ends up in the [`AnalysisReport`]
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    /// Leave out findings less severe than this
+    pub min_severity: Option<RuleSeverity>,
+    /// Keep the parsed project in the report
+    pub include_project: bool,
+}
+
+/// Configures and builds a [`DbtProjectAnalyzer`]. This is the entry point for
+/// running dbtective from Rust:
+///
+/// ```no_run
+/// use dbtective_core::{AnalyzerBuilder, ResourceFilter};
+/// use dbtective_core::types::dbt::ResourceType;
+///
+/// let report = AnalyzerBuilder::new("path/to/dbt/project")
+///     .with_filter(ResourceFilter::ResourceTypes(vec![ResourceType::Model]))
+///     .build()
+///     .analyze()?;
+/// println!("score: {}", report.overall_score);
+/// # Ok::<(), dbtective_core::DbtectiveError>(())
+/// ```
+pub struct AnalyzerBuilder {
+    source: Box<dyn FileSource>,
+    use_cache: bool,
+    vars: HashMap<String, Value>,
+    config: Option<Config>,
+    rules: Vec<Box<dyn Rule>>,
+    filters: Vec<ResourceFilter>,
+    selection: Selection,
+    output: OutputOptions,
+}
+
+impl AnalyzerBuilder {
+    /// Analyze the project in a local directory, starting from the built-in
+    /// rules, the project's config file and no filters
+    #[cfg(feature = "fs")]
+    pub fn new(project_path: impl Into<PathBuf>) -> Self {
+        Self::from_source(DirectorySource::new(project_path))
+    }
+
+    /// Analyze a project read from any [`FileSource`], e.g. an in-memory map or a tarball
+    pub fn from_source(source: impl FileSource + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            use_cache: false,
+            vars: HashMap::new(),
+            config: None,
+            rules: builtin_rules(),
+            filters: Vec::new(),
+            selection: Selection::default(),
+            output: OutputOptions::default(),
+        }
+    }
+
+    /// Reuse parse results from the project's `.dbtective/cache` for unchanged files
+    pub fn with_cache(mut self, use_cache: bool) -> Self {
+        self.use_cache = use_cache;
+        self
+    }
+
+    /// Vars overriding those in `dbt_project.yml`, like dbt's `--vars`
+    pub fn with_vars(mut self, vars: HashMap<String, Value>) -> Self {
+        self.vars = vars;
+        self
+    }
+
+    /// Use this config instead of the project's `dbtective.toml`
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Replace the rule set, e.g. to run only some built-in rules
+    pub fn with_rules(mut self, rules: Vec<Box<dyn Rule>>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Add a rule to the rule set
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Only run rules against resources matching the filter. Multiple filters must all match.
+    pub fn with_filter(mut self, filter: ResourceFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Only run rules against resources picked by dbt selectors, like
+    /// `--select`, `--exclude` and `--selector`
+    pub fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_output(mut self, output: OutputOptions) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn build(self) -> DbtProjectAnalyzer {
+        DbtProjectAnalyzer {
+            source: self.source,
+            use_cache: self.use_cache,
+            vars: self.vars,
+            config: self.config,
+            rules: self.rules,
+            filters: self.filters,
+            selection: self.selection,
+            output: self.output,
+        }
+    }
+}
+
+/// Runs the configured rules over a dbt project, see [`AnalyzerBuilder`]
+pub struct DbtProjectAnalyzer {
+    source: Box<dyn FileSource>,
+    use_cache: bool,
+    vars: HashMap<String, Value>,
+    config: Option<Config>,
+    rules: Vec<Box<dyn Rule>>,
+    filters: Vec<ResourceFilter>,
+    selection: Selection,
+    output: OutputOptions,
+}
+
+impl DbtProjectAnalyzer {
+    /// An analyzer with the default settings
+    #[cfg(feature = "fs")]
+    pub fn new(project_path: impl Into<PathBuf>) -> Self {
+        AnalyzerBuilder::new(project_path).build()
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn builder(project_path: impl Into<PathBuf>) -> AnalyzerBuilder {
+        AnalyzerBuilder::new(project_path)
+    }
+
+    /// Parse the project without running any rules
+    pub fn parse(&self) -> Result<ParseResult> {
+        DbtProjectParser::new(self.source.as_ref())
+            .with_cache(self.use_cache)
+            .with_vars(self.vars.clone())
+            .parse_project()
+    }
+
+    pub fn analyze(&self) -> Result<AnalysisReport> {
+        let parsed = self.parse()?;
+        let selectors = NamedSelectors::load(self.source.as_ref())?;
+        let selected = self.selection.select(&parsed.project, &selectors)?;
+        let applies = |resource: &dyn DbtResource| {
+            parsed
+                .project
+                .id_of(resource.unique_id())
+                .is_some_and(|id| selected.contains(&id))
+                && self.filters.iter().all(|filter| filter.matches(resource))
+        };
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => Config::discover(self.source.as_ref())?.unwrap_or_default(),
+        };
+        let custom_rules = config.custom_rules()?;
+        #[cfg(feature = "scripting")]
+        let script_rules = crate::rules::script::load_scripts(self.source.as_ref())?;
+        #[allow(unused_mut)]
+        let mut rules: Vec<&dyn Rule> = self
+            .rules
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(custom_rules.iter().map(|rule| rule as &dyn Rule))
+            .collect();
+        #[cfg(feature = "scripting")]
+        rules.extend(script_rules.iter().map(|rule| rule as &dyn Rule));
+        config.validate(&rules)?;
+        let run = engine::run_rules(&parsed.project, &rules, &config, &selectors, &applies)?;
+
+        let mut diagnostics = parsed.diagnostics;
+        diagnostics.extend(run.unjustified_suppressions.iter().map(|suppression| {
+            Diagnostic::new(
+                suppression.location.clone(),
+                "suppression ignored, the config requires a reason".to_string(),
+            )
+        }));
+        let mut findings = run.findings;
+        if let Some(min_severity) = &self.output.min_severity {
+            findings.retain(|finding| finding.severity.priority() >= min_severity.priority());
+        }
+
+        Ok(AnalysisReport {
+            project_path: self.source.describe(),
+            resource_count: parsed.project.len(),
+            diagnostics,
+            findings,
+            suppressed: run.suppressed,
+            unused_suppressions: run.unused_suppressions,
+            overall_score: scoring::overall_score(run.checks, run.failed_checks),
+            project: self.output.include_project.then_some(parsed.project),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub project_path: String,
+    pub resource_count: usize,
+    pub diagnostics: Vec<Diagnostic>,
+    pub findings: Vec<Finding>,
+    /// Number of findings left out by `dbtective: ignore` suppressions
+    #[serde(default)]
+    pub suppressed: usize,
+    /// Suppressions that no longer match any finding
+    #[serde(default)]
+    pub unused_suppressions: Vec<Suppression>,
+    pub overall_score: f64,
+    /// The parsed project, when requested with [`OutputOptions::include_project`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<DbtProject>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuleConfig;
+    use crate::rules::{RuleContext, Violation};
+    use crate::source::MemorySource;
+
+    /// Flags resources without an owner in their meta
+    struct OwnerRule;
+
+    impl Rule for OwnerRule {
+        fn id(&self) -> &str {
+            "CUSTOM_OWNER"
+        }
+
+        fn evaluate(&self, resource: &dyn DbtResource, _: &RuleContext) -> Result<Vec<Violation>> {
+            Ok(match resource.lookup("meta.owner") {
+                Some(_) => Vec::new(),
+                None => vec![Violation::new(format!("{} has no owner", resource.name()))],
+            })
+        }
+    }
+
+    fn project() -> MemorySource {
+        MemorySource::new()
+            .with_name("shop")
+            .with_file("models/orders.sql", "select 1")
+            .with_file(
+                "models/customers.sql",
+                "{{ config(meta={'owner': 'crm'}) }} select 1",
+            )
+            .with_file("seeds/countries.csv", "code\nNL\n")
+    }
+
+    #[test]
+    fn test_custom_rule_and_filter() {
+        let report = AnalyzerBuilder::from_source(project())
+            .with_rules(Vec::new())
+            .with_rule(OwnerRule)
+            .with_filter(ResourceFilter::ResourceTypes(vec![ResourceType::Model]))
+            .build()
+            .analyze()
+            .unwrap();
+
+        assert_eq!(report.resource_count, 3);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule_id, "CUSTOM_OWNER");
+        assert_eq!(report.findings[0].resource_name, "orders");
+        assert_eq!(report.findings[0].file_path, "models/orders.sql");
+        assert_eq!(report.findings[0].severity, RuleSeverity::Warning);
+        assert_eq!(report.overall_score, 50.0);
+        assert!(report.project.is_none());
+    }
+
+    #[test]
+    fn test_selection() {
+        let analyze = |select: &[&str], exclude: &[&str]| {
+            AnalyzerBuilder::from_source(project())
+                .with_rules(vec![Box::new(OwnerRule)])
+                .with_selection(Selection {
+                    select: select.iter().map(|s| s.to_string()).collect(),
+                    exclude: exclude.iter().map(|s| s.to_string()).collect(),
+                    selector: None,
+                })
+                .build()
+                .analyze()
+                .unwrap()
+        };
+
+        let report = analyze(&["resource_type:model"], &["orders"]);
+        assert!(report.findings.is_empty());
+        assert_eq!(report.overall_score, 100.0);
+        assert_eq!(report.resource_count, 3);
+
+        let report = analyze(&["path:models", "path:seeds"], &[]);
+        assert_eq!(report.findings.len(), 2);
+    }
+
+    #[test]
+    fn test_config_and_output_options() {
+        let analyzer = |config: Config| {
+            AnalyzerBuilder::from_source(project())
+                .with_rules(vec![Box::new(OwnerRule)])
+                .with_config(config)
+                .with_output(OutputOptions {
+                    min_severity: Some(RuleSeverity::Error),
+                    include_project: true,
+                })
+                .build()
+        };
+
+        // Warnings are left out of the report, but still count towards the score
+        let report = analyzer(Config::default()).analyze().unwrap();
+        assert!(report.findings.is_empty());
+        assert!(report.overall_score < 100.0);
+        assert_eq!(report.project.map(|project| project.len()), Some(3));
+
+        let escalated = Config::default().with_rule(
+            "CUSTOM_OWNER",
+            RuleConfig {
+                severity: Some(RuleSeverity::Error),
+                ..RuleConfig::default()
+            },
+        );
+        assert_eq!(analyzer(escalated).analyze().unwrap().findings.len(), 2);
+
+        let disabled = Config::default().with_rule(
+            "CUSTOM_OWNER",
+            RuleConfig {
+                enabled: Some(false),
+                ..RuleConfig::default()
+            },
+        );
+        assert_eq!(analyzer(disabled).analyze().unwrap().overall_score, 100.0);
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let source = project()
+            .with_file(
+                "models/legacy.sql",
+                "-- dbtective: ignore=CUSTOM_OWNER reason=\"retired soon\"\nselect 1",
+            )
+            .with_file(
+                "models/schema.yml",
+                r#"
+version: 2
+models:
+  - name: orders  # dbtective: ignore=DOC001
+    meta:
+      dbtective:
+        ignore: [CUSTOM_OWNER]
+  - name: customers
+    # dbtective: ignore=CUSTOM_OWNER reason="has an owner"
+"#,
+            );
+        let analyze = |config: Config| {
+            AnalyzerBuilder::from_source(source.clone())
+                .with_rules(vec![Box::new(OwnerRule)])
+                .with_config(config)
+                .with_filter(ResourceFilter::ResourceTypes(vec![ResourceType::Model]))
+                .build()
+                .analyze()
+                .unwrap()
+        };
+
+        // DOC001 doesn't run, so its suppression isn't reported as unused
+        let report = analyze(Config::default());
+        assert!(report.findings.is_empty());
+        assert_eq!(report.suppressed, 2);
+        assert_eq!(report.overall_score, 100.0);
+        assert_eq!(
+            report
+                .unused_suppressions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["models/schema.yml:9: ignore=CUSTOM_OWNER (has an owner)"]
+        );
+
+        let mut strict = Config::default();
+        strict.suppressions.require_reason = true;
+        let report = analyze(strict);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].resource_name, "orders");
+        assert_eq!(
+            report
+                .diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "models/schema.yml:4: suppression ignored, the config requires a reason",
+                "models/schema.yml (meta of orders): suppression ignored, the config requires a reason",
+            ]
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use crate::error::{DbtectiveError, Result};
+use crate::rules::custom::CustomRule;
+use crate::rules::registry::RuleRegistry;
+use crate::rules::{Rule, RuleOptions};
+use crate::selector::{SelectionCriteria, SelectionSpec, SelectorMethod};
+use crate::source::FileSource;
+use crate::types::dbt::ResourceType;
+use crate::types::severity::RuleSeverity;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Config file looked up in the project root
+pub const CONFIG_FILE: &str = "dbtective.toml";
+
+/// Fallback config file, read from its `[tool.dbtective]` table
+pub const PYPROJECT_FILE: &str = "pyproject.toml";
+
+/// Which rules run, with what severity and options:
+///
+/// ```toml
+/// [rules.DOC001]
+/// severity = "error"
+/// min_description_length = 20
+///
+/// [rules.model-tests]
+/// enabled = false
+///
+/// [rules.TEST002.include]
+/// paths = ["models/marts"]
+///
+/// [suppressions]
+/// require_reason = true
+///
+/// [[custom_rules]]
+/// id = "MARTS_OWNER"
+/// select = "path:models/marts"
+/// message = "{name} has no owner"
+/// conditions = [{ field = "meta.owner", exists = true }]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Keyed by rule id (`DOC001`) or name (`description`)
+    #[serde(default)]
+    pub rules: HashMap<String, RuleConfig>,
+    #[serde(default)]
+    pub suppressions: SuppressionConfig,
+    /// Rules defined here rather than in code, see [`CustomRule`]
+    #[serde(default)]
+    pub custom_rules: Vec<CustomRuleConfig>,
+    /// File the config was read from, for error messages
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// How `dbtective: ignore` comments and `meta` entries are handled
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuppressionConfig {
+    /// Only honour suppressions that give a `reason`
+    #[serde(default)]
+    pub require_reason: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<RuleSeverity>,
+    /// Only check resources in one of these scopes, all of them when empty
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub include: Vec<RuleScope>,
+    /// Skip resources in any of these scopes
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub exclude: Vec<RuleScope>,
+    /// Rule options, checked against the rule's schema
+    #[serde(flatten)]
+    pub options: HashMap<String, Value>,
+}
+
+/// A predicate rule written in the config. Each resource it selects must
+/// meet every condition; each failed one is a finding.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRuleConfig {
+    pub id: String,
+    /// Finding message, with `{name}`, `{resource_type}`, `{unique_id}`,
+    /// `{field}` and `{expectation}` placeholders
+    pub message: Option<String>,
+    pub severity: Option<RuleSeverity>,
+    /// Resource types checked, all of them when empty
+    #[serde(default)]
+    pub resource_types: Vec<ResourceType>,
+    /// A node selector expression, as given to `--select`
+    pub select: Option<String>,
+    pub conditions: Vec<Condition>,
+}
+
+/// Checks on the value at a field path like `meta.owner.email` or
+/// `columns[*].description`. Every check given must pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    pub field: String,
+    /// Whether the field must be set (`true`) or absent (`false`)
+    pub exists: Option<bool>,
+    pub equals: Option<Value>,
+    /// A regex the value must match
+    pub matches: Option<String>,
+    /// Minimum length of a string or list
+    pub min_length: Option<usize>,
+    /// Values allowed, for a list every item must be one of them
+    #[serde(rename = "in")]
+    pub one_of: Option<Vec<Value>>,
+}
+
+/// Resources a rule applies to. A resource is in the scope when it matches
+/// every kind of criteria given, and any value of each.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleScope {
+    #[serde(default)]
+    pub resource_types: Vec<ResourceType>,
+    /// Directories or path globs relative to the project root
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// A node selector expression, as given to `--select`
+    pub select: Option<String>,
+}
+
+impl RuleScope {
+    /// The scope as a selection, `None` when it has no criteria and so
+    /// covers every resource
+    pub fn spec(&self) -> Result<Option<SelectionSpec>> {
+        let any = |method: SelectorMethod, values: Vec<&str>| {
+            SelectionSpec::Union(
+                values
+                    .into_iter()
+                    .map(|value| {
+                        SelectionSpec::Criteria(SelectionCriteria::new(method.clone(), value))
+                    })
+                    .collect(),
+            )
+        };
+
+        let mut criteria = Vec::new();
+        if !self.resource_types.is_empty() {
+            let types = self.resource_types.iter().map(ResourceType::as_str);
+            criteria.push(any(SelectorMethod::ResourceType, types.collect()));
+        }
+        for (method, values) in [
+            (SelectorMethod::Path, &self.paths),
+            (SelectorMethod::Tag, &self.tags),
+            (SelectorMethod::Package, &self.packages),
+        ] {
+            if !values.is_empty() {
+                criteria.push(any(method, values.iter().map(String::as_str).collect()));
+            }
+        }
+        if let Some(select) = &self.select {
+            criteria.push(SelectionSpec::parse_cli(&[select])?);
+        }
+
+        Ok((!criteria.is_empty()).then_some(SelectionSpec::Intersection(criteria)))
+    }
+}
+
+/// A single scope table, or a list of them
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<RuleScope>, D::Error> {
+    struct ScopesVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ScopesVisitor {
+        type Value = Vec<RuleScope>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a scope table or a list of them")
+        }
+
+        fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            RuleScope::deserialize(MapAccessDeserializer::new(map)).map(|scope| vec![scope])
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+            Vec::deserialize(SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    deserializer.deserialize_any(ScopesVisitor)
+}
+
+#[derive(Deserialize)]
+struct PyProject {
+    tool: Option<PyProjectTools>,
+}
+
+#[derive(Deserialize)]
+struct PyProjectTools {
+    dbtective: Option<Config>,
+}
+
+impl Config {
+    /// Find `dbtective.toml` in the project root, or a `pyproject.toml` with
+    /// a `[tool.dbtective]` table
+    pub fn discover(source: &dyn FileSource) -> Result<Option<Self>> {
+        let path = Path::new(CONFIG_FILE);
+        if source.exists(path) {
+            return Self::parse(&source.read_to_string(path)?, path).map(Some);
+        }
+
+        let path = Path::new(PYPROJECT_FILE);
+        if source.exists(path) {
+            return Self::parse_pyproject(&source.read_to_string(path)?, path);
+        }
+
+        Ok(None)
+    }
+
+    /// Read a config file given explicitly, e.g. with `--config`
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| DbtectiveError::io(path, e))?;
+        if path.file_name().is_some_and(|name| name == PYPROJECT_FILE) {
+            return Self::parse_pyproject(&content, path)?.ok_or_else(|| DbtectiveError::Config {
+                path: Some(path.to_path_buf()),
+                line: None,
+                message: "no [tool.dbtective] table".to_string(),
+            });
+        }
+        Self::parse(&content, path)
+    }
+
+    /// Parse the content of a `dbtective.toml`
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let mut config: Self = toml::from_str(content).map_err(|e| toml_error(path, content, e))?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Parse the `[tool.dbtective]` table of a `pyproject.toml`, if it has one
+    pub fn parse_pyproject(content: &str, path: &Path) -> Result<Option<Self>> {
+        let pyproject: PyProject =
+            toml::from_str(content).map_err(|e| toml_error(path, content, e))?;
+        Ok(pyproject
+            .tool
+            .and_then(|tool| tool.dbtective)
+            .map(|config| Self {
+                path: Some(path.to_path_buf()),
+                ..config
+            }))
+    }
+
+    pub fn with_rule(mut self, id: &str, rule: RuleConfig) -> Self {
+        self.rules.insert(id.to_string(), rule);
+        self
+    }
+
+    /// Configuration of a rule, by its id or registry name
+    pub fn rule(&self, id: &str) -> Option<&RuleConfig> {
+        self.rules.get(id).or_else(|| {
+            RuleRegistry::get_rule_by_str(id).and_then(|info| self.rules.get(info.name))
+        })
+    }
+
+    /// Rules are enabled unless configured otherwise
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.rule(id).and_then(|rule| rule.enabled).unwrap_or(true)
+    }
+
+    /// Severity override for a rule, if any
+    pub fn severity(&self, id: &str) -> Option<RuleSeverity> {
+        self.rule(id).and_then(|rule| rule.severity.clone())
+    }
+
+    /// Configured options of a rule, without defaults
+    pub fn options(&self, id: &str) -> HashMap<String, Value> {
+        self.rule(id)
+            .map(|rule| rule.options.clone())
+            .unwrap_or_default()
+    }
+
+    /// Rules defined in `[[custom_rules]]`
+    pub fn custom_rules(&self) -> Result<Vec<CustomRule>> {
+        self.custom_rules
+            .iter()
+            .map(|rule| {
+                CustomRule::new(rule.clone()).map_err(|e| match e {
+                    DbtectiveError::Config { message, .. } => self.error(message),
+                    other => other,
+                })
+            })
+            .collect()
+    }
+
+    /// Check that every configured rule exists, is configured once, and only
+    /// sets options its schema declares with the right types. Rules in the
+    /// registry may be configured even when they aren't in `rules`.
+    pub fn validate(&self, rules: &[&dyn Rule]) -> Result<()> {
+        for (index, rule) in rules.iter().enumerate() {
+            if rules[..index].iter().any(|other| other.id() == rule.id()) {
+                return Err(self.error(format!("rule {} is defined more than once", rule.id())));
+            }
+        }
+
+        let mut keys: Vec<&String> = self.rules.keys().collect();
+        keys.sort();
+
+        for key in keys {
+            let rule = rules
+                .iter()
+                .find(|rule| rule.id() == key || rule.info().is_some_and(|i| i.name == key));
+            let id = match (
+                rule,
+                RuleRegistry::get_all_rules().iter().find(|i| i.name == key),
+            ) {
+                (Some(rule), _) => rule.id(),
+                (None, Some(info)) => info.id.as_str(),
+                (None, None) if RuleRegistry::get_rule_by_str(key).is_some() => key.as_str(),
+                (None, None) => return Err(self.error(format!("unknown rule '{}'", key))),
+            };
+
+            let name = RuleRegistry::get_rule_by_str(id).map(|info| info.name);
+            if key != id && self.rules.contains_key(id)
+                || key == id && name.is_some_and(|n| self.rules.contains_key(n))
+            {
+                return Err(self.error(format!(
+                    "rule {} is configured both by id and by name '{}'",
+                    id,
+                    name.unwrap_or_default()
+                )));
+            }
+
+            let rule_config = &self.rules[key];
+            for scope in rule_config.include.iter().chain(&rule_config.exclude) {
+                scope
+                    .spec()
+                    .map_err(|e| self.error(format!("rules.{}: {}", key, e)))?;
+            }
+
+            if let Some(rule) = rule {
+                RuleOptions::resolve(key, &rule.options_schema(), &self.rules[key].options)
+                    .map_err(|e| match e {
+                        DbtectiveError::Config { message, .. } => self.error(message),
+                        other => other,
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn error(&self, message: String) -> DbtectiveError {
+        DbtectiveError::Config {
+            path: self.path.clone(),
+            line: None,
+            message,
+        }
+    }
+}
+
+fn toml_error(path: &Path, content: &str, error: toml::de::Error) -> DbtectiveError {
+    let line = error
+        .span()
+        .map(|span| content[..span.start].matches('\n').count() + 1);
+    DbtectiveError::Config {
+        path: Some(path.to_path_buf()),
+        line,
+        message: error.message().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::builtin_rules;
+    use crate::source::MemorySource;
+
+    #[test]
+    fn test_rules_by_id_or_name() {
+        let config = Config::parse(
+            r#"
+[rules.DOC001]
+severity = "error"
+
+[rules.model-tests]
+enabled = false
+"#,
+            Path::new(CONFIG_FILE),
+        )
+        .unwrap();
+
+        assert_eq!(config.severity("DOC001"), Some(RuleSeverity::Error));
+        assert!(!config.is_enabled("TEST002"));
+        assert!(config.is_enabled("TEST001"));
+        let rules = builtin_rules();
+        config
+            .validate(&rules.iter().map(AsRef::as_ref).collect::<Vec<_>>())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_discover_pyproject() {
+        let source = MemorySource::new().with_file(
+            PYPROJECT_FILE,
+            "[project]\nname = \"analytics\"\n\n[tool.dbtective.rules.description]\nenabled = false\n",
+        );
+        let config = Config::discover(&source).unwrap().unwrap();
+        assert!(!config.is_enabled("DOC001"));
+        assert_eq!(config.path.as_deref(), Some(Path::new(PYPROJECT_FILE)));
+
+        let source = source.with_file(CONFIG_FILE, "[rules.DOC001]\nseverity = \"info\"\n");
+        let config = Config::discover(&source).unwrap().unwrap();
+        assert!(config.is_enabled("DOC001"));
+
+        let source = MemorySource::new().with_file(PYPROJECT_FILE, "[project]\nname = \"x\"\n");
+        assert!(Config::discover(&source).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let parse = |content: &str| Config::parse(content, Path::new(CONFIG_FILE));
+
+        let error = parse("[rules.DOC001]\nseverity = \"fatal\"\n").unwrap_err();
+        assert!(matches!(
+            error,
+            DbtectiveError::Config { line: Some(2), .. }
+        ));
+        assert!(error.to_string().starts_with("dbtective.toml:2: "));
+
+        let error = parse("[rulez.DOC001]\nenabled = false\n").unwrap_err();
+        assert!(error.to_string().contains("unknown field `rulez`"));
+
+        let rules = builtin_rules();
+        let rules: Vec<&dyn Rule> = rules.iter().map(AsRef::as_ref).collect();
+        let validate = |content: &str| parse(content).unwrap().validate(&rules);
+        assert_eq!(
+            validate("[rules.DOC999]\nenabled = false\n")
+                .unwrap_err()
+                .to_string(),
+            "dbtective.toml: unknown rule 'DOC999'"
+        );
+        assert!(validate("[rules.DOC001]\n[rules.description]\n").is_err());
+        assert!(validate("[rules.description]\nmin_length = 3\n")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown option 'min_length'"));
+    }
+}
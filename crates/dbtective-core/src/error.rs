@@ -0,0 +1,131 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+pub type Result<T, E = DbtectiveError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while analyzing a project
+#[derive(Debug, thiserror::Error)]
+pub enum DbtectiveError {
+    /// The project directory doesn't exist
+    #[error("Project directory not found: {}", .path.display())]
+    ProjectNotFound { path: PathBuf },
+
+    /// A project file couldn't be read or written
+    #[error("{}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A schema (properties) file isn't valid YAML or doesn't have the expected shape
+    #[error("{}: {message}", location(.path, *.line, *.column))]
+    Yaml {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// Invalid project or dbtective configuration, e.g. `dbt_project.yml` or `--vars`
+    #[error("{}{message}", prefix(.path.as_deref(), *.line))]
+    Config {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        message: String,
+    },
+
+    /// A git revision (or the project in it) couldn't be read
+    #[error("Git revision {rev}: {message}")]
+    Git { rev: String, message: String },
+
+    /// A rule failed to run
+    #[error("Rule {rule_id} failed: {message}")]
+    Rule { rule_id: String, message: String },
+
+    /// A report or inventory couldn't be written
+    #[error("Failed to write output: {message}")]
+    Output { message: String },
+}
+
+impl DbtectiveError {
+    pub fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// A YAML error in a schema file, keeping serde_yaml's line and column
+    pub fn yaml(path: &Path, error: serde_yaml::Error) -> Self {
+        let location = error.location();
+        Self::Yaml {
+            path: path.to_path_buf(),
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message: error.to_string(),
+        }
+    }
+
+    /// An invalid configuration file, keeping serde_yaml's line if there is one
+    pub fn config_yaml(path: &Path, error: serde_yaml::Error) -> Self {
+        Self::Config {
+            path: Some(path.to_path_buf()),
+            line: error.location().map(|l| l.line()),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn git(rev: &str, error: impl Display) -> Self {
+        Self::Git {
+            rev: rev.to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn config(message: impl Display) -> Self {
+        Self::Config {
+            path: None,
+            line: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn output(error: impl Display) -> Self {
+        Self::Output {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// `path`, `path:line` or `path:line:column`
+fn location(path: &Path, line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", path.display(), line, column),
+        (Some(line), None) => format!("{}:{}", path.display(), line),
+        _ => path.display().to_string(),
+    }
+}
+
+fn prefix(path: Option<&Path>, line: Option<usize>) -> String {
+    path.map(|path| format!("{}: ", location(path, line, None)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_error_keeps_location() {
+        let error = serde_yaml::from_str::<serde_yaml::Value>("models:\n  - name: [unclosed\n")
+            .unwrap_err();
+        let error = DbtectiveError::yaml(Path::new("models/schema.yml"), error);
+
+        match &error {
+            DbtectiveError::Yaml { line, .. } => assert!(line.is_some()),
+            other => panic!("expected a YAML error, got {:?}", other),
+        }
+        assert!(error.to_string().starts_with("models/schema.yml:"));
+    }
+}
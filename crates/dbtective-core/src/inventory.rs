@@ -0,0 +1,185 @@
+use crate::error::{DbtectiveError, Result};
+use crate::types::dbt::{DbtProject, DbtResource};
+use serde::Serialize;
+use std::io::Write;
+
+/// Output format of `dbtective inventory`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryFormat {
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl InventoryFormat {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// One CSV line per resource. Lists are comma separated, meta and config are JSON.
+#[derive(Serialize)]
+struct InventoryRow<'a> {
+    unique_id: &'a str,
+    resource_type: &'static str,
+    name: &'a str,
+    package_name: &'a str,
+    fqn: String,
+    original_file_path: String,
+    patch_path: String,
+    enabled: bool,
+    description: &'a str,
+    tags: String,
+    tests: String,
+    columns: String,
+    depends_on: String,
+    meta: String,
+    config: String,
+}
+
+impl<'a> InventoryRow<'a> {
+    fn new(resource: &'a dyn DbtResource) -> Result<Self> {
+        let path = |path: Option<&std::path::Path>| {
+            path.map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let columns: Vec<&str> = resource.columns().iter().map(|c| c.name.as_str()).collect();
+
+        Ok(Self {
+            unique_id: resource.unique_id(),
+            resource_type: resource.resource_type().as_str(),
+            name: resource.name(),
+            package_name: resource.package_name(),
+            fqn: resource.fqn().join("."),
+            original_file_path: path(resource.original_file_path()),
+            patch_path: path(resource.patch_path()),
+            enabled: resource.enabled(),
+            description: resource.description().as_deref().unwrap_or_default(),
+            tags: resource.tags().join(","),
+            tests: resource
+                .tests()
+                .iter()
+                .map(|test| test.name.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            columns: columns.join(","),
+            depends_on: resource.depends_on().join(","),
+            // Going through `serde_json::Value` sorts the keys
+            meta: serde_json::to_value(resource.meta())
+                .map_err(DbtectiveError::output)?
+                .to_string(),
+            config: serde_json::to_value(resource.config())
+                .map_err(DbtectiveError::output)?
+                .to_string(),
+        })
+    }
+}
+
+/// Write every resource of the project, with its merged config, in the given format
+pub fn write_inventory<W: Write>(
+    project: &DbtProject,
+    format: InventoryFormat,
+    mut writer: W,
+) -> Result<()> {
+    match format {
+        InventoryFormat::Json | InventoryFormat::Yaml => {
+            // Converting to `serde_json::Value` first gives a stable key order
+            let nodes: Vec<_> = project.nodes().map(|(_, node)| node).collect();
+            let inventory = serde_json::to_value(nodes).map_err(DbtectiveError::output)?;
+
+            if format == InventoryFormat::Json {
+                serde_json::to_writer_pretty(&mut writer, &inventory)
+                    .map_err(DbtectiveError::output)?;
+                writeln!(writer).map_err(DbtectiveError::output)?;
+            } else {
+                serde_yaml::to_writer(&mut writer, &inventory).map_err(DbtectiveError::output)?;
+            }
+        }
+        InventoryFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(writer);
+            for resource in project.resources() {
+                csv_writer
+                    .serialize(InventoryRow::new(resource)?)
+                    .map_err(DbtectiveError::output)?;
+            }
+            csv_writer.flush().map_err(DbtectiveError::output)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dbt::{Column, DbtNode, Model, Source};
+
+    fn project() -> DbtProject {
+        let mut project = DbtProject::new("shop".to_string());
+        let source = project.add_node(DbtNode::Source(Source::new(
+            "orders".to_string(),
+            "raw".to_string(),
+        )));
+
+        let mut model = Model::new("stg_orders".to_string()).with_description("Orders".to_string());
+        model.sql_file = Some("models/staging/stg_orders.sql".into());
+        model.tags = vec!["daily".to_string(), "core".to_string()];
+        model.columns = vec![Column::new("id".to_string())];
+        model
+            .config
+            .insert("materialized".to_string(), "view".into());
+        let model = project.add_node(DbtNode::Model(model));
+        project.add_dependency(model, source);
+        project
+    }
+
+    #[test]
+    fn test_json_inventory() {
+        let mut output = Vec::new();
+        write_inventory(&project(), InventoryFormat::Json, &mut output).unwrap();
+
+        let inventory: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let model = &inventory[1];
+        assert_eq!(model["resource_type"], "model");
+        assert_eq!(model["unique_id"], "model.shop.stg_orders");
+        assert_eq!(
+            model["fqn"],
+            serde_json::json!(["shop", "staging", "stg_orders"])
+        );
+        assert_eq!(model["config"]["materialized"], "view");
+        assert_eq!(model["depends_on"][0], "source.shop.raw.orders");
+
+        // The JSON inventory deserializes back into nodes
+        let nodes: Vec<DbtNode> = serde_json::from_slice(&output).unwrap();
+        assert_eq!(nodes[1].name(), "stg_orders");
+    }
+
+    #[test]
+    fn test_csv_inventory() {
+        let mut output = Vec::new();
+        write_inventory(&project(), InventoryFormat::Csv, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("unique_id,resource_type,name,package_name,fqn"));
+        assert!(lines[2].starts_with(
+            "model.shop.stg_orders,model,stg_orders,shop,shop.staging.stg_orders,models/staging/stg_orders.sql,,true,Orders,\"daily,core\""
+        ));
+        assert!(lines[2].ends_with(r#""{""materialized"":""view""}""#));
+    }
+}
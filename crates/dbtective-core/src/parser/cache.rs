@@ -0,0 +1,226 @@
+#[cfg(feature = "fs")]
+use crate::error::{DbtectiveError, Result};
+use crate::parser::{sql::SqlResourceInfo, yaml::YamlResourceConfig};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the project root) holding dbtective's on-disk cache
+pub const CACHE_DIR: &str = ".dbtective/cache";
+
+#[cfg(feature = "fs")]
+const CACHE_FILE: &str = "parse-cache.json";
+
+/// The parsed result of a single project file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CachedParse {
+    Sql(Box<SqlResourceInfo>),
+    Yaml(HashMap<String, YamlResourceConfig>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    content_hash: String,
+    parsed: CachedParse,
+}
+
+#[cfg(feature = "fs")]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// Incremental parse cache keyed by file path and content hash.
+///
+/// Entries written by a different dbtective version are discarded, and only
+/// files seen during the current run are written back, so deleted files are
+/// pruned automatically. Loading and saving require the `fs` feature.
+pub struct ParseCache {
+    #[cfg(feature = "fs")]
+    cache_path: PathBuf,
+    previous: HashMap<String, CacheEntry>,
+    current: HashMap<String, CacheEntry>,
+}
+
+impl ParseCache {
+    /// Load the cache for a project. A missing, unreadable or outdated cache
+    /// file simply results in an empty cache.
+    #[cfg(feature = "fs")]
+    pub fn load(project_path: &Path) -> Self {
+        let cache_path = project_path.join(CACHE_DIR).join(CACHE_FILE);
+        let previous = std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| cache.version == env!("CARGO_PKG_VERSION"))
+            .map(|cache| cache.entries)
+            .unwrap_or_default();
+
+        Self {
+            cache_path,
+            previous,
+            current: HashMap::new(),
+        }
+    }
+
+    /// Look up a file's parsed result, returning it only if the content is unchanged
+    pub fn get(&mut self, key: &str, content: &str) -> Option<CachedParse> {
+        let hash = content_hash(content);
+        let entry = self.previous.remove(key)?;
+        if entry.content_hash != hash {
+            return None;
+        }
+
+        let parsed = entry.parsed.clone();
+        self.current.insert(key.to_string(), entry);
+        Some(parsed)
+    }
+
+    /// Record a freshly parsed file
+    pub fn insert(&mut self, key: &str, content: &str, parsed: CachedParse) {
+        self.current.insert(
+            key.to_string(),
+            CacheEntry {
+                content_hash: content_hash(content),
+                parsed,
+            },
+        );
+    }
+
+    /// Write the entries seen during this run back to disk
+    #[cfg(feature = "fs")]
+    pub fn save(self) -> Result<()> {
+        if let Some(parent) = self.cache_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DbtectiveError::io(parent, e))?;
+        }
+
+        let cache = CacheFile {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            entries: self.current,
+        };
+        let content = serde_json::to_string(&cache).map_err(DbtectiveError::output)?;
+        std::fs::write(&self.cache_path, content)
+            .map_err(|e| DbtectiveError::io(&self.cache_path, e))?;
+
+        Ok(())
+    }
+
+    /// Remove the cache directory of a project. Returns whether anything was removed.
+    #[cfg(feature = "fs")]
+    pub fn clean(project_path: &Path) -> Result<bool> {
+        let cache_dir = project_path.join(CACHE_DIR);
+        if !cache_dir.exists() {
+            return Ok(false);
+        }
+
+        std::fs::remove_dir_all(&cache_dir).map_err(|e| DbtectiveError::io(&cache_dir, e))?;
+        Ok(true)
+    }
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use super::*;
+    use crate::parser::project::ResolveContext;
+    use crate::parser::sql::parse_sql_file;
+
+    fn sql_entry(content: &str) -> CachedParse {
+        CachedParse::Sql(Box::new(
+            parse_sql_file(
+                Path::new("models/users.sql"),
+                content,
+                &ResolveContext::default(),
+            )
+            .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "{{ config(materialized='table') }}\nselect 1";
+
+        let mut cache = ParseCache::load(dir.path());
+        assert!(cache.get("models/users.sql", content).is_none());
+        cache.insert("models/users.sql", content, sql_entry(content));
+        cache.save().unwrap();
+
+        let mut cache = ParseCache::load(dir.path());
+        match cache.get("models/users.sql", content) {
+            Some(CachedParse::Sql(info)) => {
+                assert_eq!(info.name, "users");
+                assert_eq!(info.sql_config.materialized, Some("table".to_string()));
+            }
+            _ => panic!("expected a cached SQL entry"),
+        }
+    }
+
+    #[test]
+    fn test_cache_miss_on_changed_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "select 1";
+
+        let mut cache = ParseCache::load(dir.path());
+        cache.insert("models/users.sql", content, sql_entry(content));
+        cache.save().unwrap();
+
+        let mut cache = ParseCache::load(dir.path());
+        assert!(cache.get("models/users.sql", "select 2").is_none());
+    }
+
+    #[test]
+    fn test_cache_discards_other_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "select 1";
+
+        let mut cache = ParseCache::load(dir.path());
+        cache.insert("models/users.sql", content, sql_entry(content));
+        cache.save().unwrap();
+
+        let cache_file = dir.path().join(CACHE_DIR).join(CACHE_FILE);
+        let raw = std::fs::read_to_string(&cache_file).unwrap();
+        let raw = raw.replace(env!("CARGO_PKG_VERSION"), "0.0.0-old");
+        std::fs::write(&cache_file, raw).unwrap();
+
+        let mut cache = ParseCache::load(dir.path());
+        assert!(cache.get("models/users.sql", content).is_none());
+    }
+
+    #[test]
+    fn test_unwritable_cache_is_a_diagnostic() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("dbt_project.yml"), "name: shop\n").unwrap();
+        std::fs::create_dir(dir.path().join("models")).unwrap();
+        std::fs::write(dir.path().join("models/users.sql"), "select 1").unwrap();
+        // A file where the cache directory should go
+        std::fs::write(dir.path().join(".dbtective"), "").unwrap();
+
+        let source = crate::source::DirectorySource::new(dir.path());
+        let parsed = crate::parser::DbtProjectParser::new(&source)
+            .with_cache(true)
+            .parse_project()
+            .unwrap();
+        assert_eq!(parsed.project.len(), 1);
+        assert_eq!(parsed.diagnostics.len(), 1);
+        assert!(parsed.diagnostics[0]
+            .message
+            .starts_with("parse cache not saved: "));
+    }
+
+    #[test]
+    fn test_clean_removes_cache_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!ParseCache::clean(dir.path()).unwrap());
+
+        let cache = ParseCache::load(dir.path());
+        cache.save().unwrap();
+        assert!(ParseCache::clean(dir.path()).unwrap());
+        assert!(!dir.path().join(CACHE_DIR).exists());
+    }
+}
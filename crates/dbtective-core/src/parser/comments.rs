@@ -0,0 +1,308 @@
+use crate::types::suppression::Suppression;
+use std::path::Path;
+
+/// A `dbtective: ignore=DOC001,TEST003 reason="legacy"` comment, with the
+/// rule list and reason both optional
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    pub rules: Vec<String>,
+    pub reason: Option<String>,
+}
+
+impl Directive {
+    /// Parse the text of a comment, without its `--` or `#` marker
+    pub fn parse(comment: &str) -> Option<Self> {
+        let rest = comment.trim().strip_prefix("dbtective:")?.trim_start();
+        let rest = rest.strip_prefix("ignore")?;
+        let (rules, rest) = match rest.strip_prefix('=') {
+            Some(rest) => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let rules = rest[..end]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|rule| !rule.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                (rules, &rest[end..])
+            }
+            None if rest.is_empty() || rest.starts_with(char::is_whitespace) => (Vec::new(), rest),
+            None => return None,
+        };
+        let reason = rest
+            .trim_start()
+            .strip_prefix("reason=")
+            .map(|reason| match reason.strip_prefix('"') {
+                Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+                None => reason.split_whitespace().next().unwrap_or_default(),
+            })
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string);
+
+        Some(Self { rules, reason })
+    }
+
+    fn suppression(self, file_path: &Path, line: usize) -> Suppression {
+        Suppression::new(
+            String::new(),
+            self.rules,
+            format!("{}:{}", file_path.to_string_lossy(), line),
+        )
+        .with_reason(self.reason)
+    }
+}
+
+/// `-- dbtective: ignore` comments in a SQL file, covering the whole
+/// resource. The unique id is filled in once the resource exists.
+pub fn sql_suppressions(file_path: &Path, content: &str) -> Vec<Suppression> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (_, comment) = line.split_once("--")?;
+            Directive::parse(comment).map(|directive| directive.suppression(file_path, index + 1))
+        })
+        .collect()
+}
+
+/// What a `# dbtective: ignore` comment in a properties file applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlTarget {
+    /// A model, seed or snapshot, a source table as `source.table`, or an
+    /// exposure or macro as `exposure.name` or `macro.name`
+    Resource(String),
+    /// Every table of a source
+    Source(String),
+}
+
+/// A resource, and one of its columns if any
+type Target = (YamlTarget, Option<String>);
+
+/// `# dbtective: ignore` comments in a properties file. A comment applies to
+/// the entry it's written in, or to the entry on the next line when it sits
+/// just above one, like:
+///
+/// ```yaml
+/// models:
+///   # dbtective: ignore=DOC001 reason="generated"
+///   - name: orders
+///     columns:
+///       - name: order_id  # dbtective: ignore=TEST003
+/// ```
+pub fn yaml_suppressions(file_path: &Path, content: &str) -> Vec<(YamlTarget, Suppression)> {
+    let mut entries: Vec<Entry> = Vec::new();
+    // A directive on a line of its own, waiting to see what follows it
+    let mut pending: Option<(usize, usize, Directive, Option<Target>)> = None;
+    let mut suppressions = Vec::new();
+    let mut suppress = |target: Option<Target>, line: usize, directive: Directive| {
+        if let Some((target, column)) = target {
+            let mut suppression = directive.suppression(file_path, line);
+            suppression.column = column;
+            suppressions.push((target, suppression));
+        }
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let indent = line.len() - line.trim_start().len();
+        let (code, comment) = split_comment(line);
+        let directive = comment.and_then(Directive::parse);
+        let code = code.trim();
+        if code.is_empty() {
+            if let Some(directive) = directive {
+                let enclosing = target(&entries);
+                pending = Some((index + 1, indent, directive, enclosing));
+            }
+            continue;
+        }
+
+        let is_item = code == "-" || code.starts_with("- ");
+        // A list may be indented as far as its key
+        while entries.last().is_some_and(|entry| match entry {
+            Entry::Key { indent: key, .. } => *key > indent || (*key == indent && !is_item),
+            Entry::Item { indent: item, .. } => *item >= indent,
+        }) {
+            entries.pop();
+        }
+        if is_item {
+            let name = code[1..].trim_start().strip_prefix("name:").map(unquote);
+            entries.push(Entry::Item { indent, name });
+        } else if let Some((key, value)) = code.split_once(':') {
+            let value = value.trim();
+            match entries.last_mut() {
+                Some(Entry::Item { name, .. }) if key == "name" && name.is_none() => {
+                    *name = Some(unquote(value));
+                }
+                _ if value.is_empty() => entries.push(Entry::Key {
+                    indent,
+                    key: key.trim().to_string(),
+                }),
+                _ => {}
+            }
+        }
+
+        if let Some((line, comment_indent, directive, enclosing)) = pending.take() {
+            // A comment lined up with the next item is about that item
+            if is_item && comment_indent == indent {
+                suppress(target(&entries), line, directive);
+            } else {
+                suppress(enclosing, line, directive);
+            }
+        }
+        if let Some(directive) = directive {
+            suppress(target(&entries), index + 1, directive);
+        }
+    }
+    if let Some((line, _, directive, enclosing)) = pending {
+        suppress(enclosing, line, directive);
+    }
+
+    suppressions
+}
+
+enum Entry {
+    /// A `key:` opening a block
+    Key { indent: usize, key: String },
+    /// A `- name: ...` list item
+    Item { indent: usize, name: Option<String> },
+}
+
+/// Resource and column lists a suppression can be attached in
+const TARGET_LISTS: &[&str] = &[
+    "models",
+    "seeds",
+    "snapshots",
+    "exposures",
+    "macros",
+    "sources",
+    "tables",
+    "columns",
+];
+
+/// The resource, and column if any, of the innermost resource or column entry
+fn target(entries: &[Entry]) -> Option<Target> {
+    // (list key, item name) pairs from the outermost in, skipping lists
+    // like `tests` that don't hold resources or columns
+    let mut items = Vec::new();
+    let mut list = "";
+    for entry in entries {
+        match entry {
+            Entry::Key { key, .. } => list = key,
+            Entry::Item { name, .. } if TARGET_LISTS.contains(&list) => {
+                items.push((list, name.as_deref()?))
+            }
+            Entry::Item { .. } => {}
+        }
+    }
+
+    let (column, resource) = match items.last()? {
+        ("columns", column) => (Some(column.to_string()), &items[..items.len() - 1]),
+        _ => (None, &items[..]),
+    };
+    let target = match resource {
+        [.., ("sources", source), ("tables", table)] => {
+            YamlTarget::Resource(format!("{}.{}", source, table))
+        }
+        [.., ("sources", source)] if column.is_none() => YamlTarget::Source(source.to_string()),
+        [.., ("models" | "seeds" | "snapshots", name)] => YamlTarget::Resource(name.to_string()),
+        [.., ("exposures", name)] => YamlTarget::Resource(format!("exposure.{}", name)),
+        [.., ("macros", name)] => YamlTarget::Resource(format!("macro.{}", name)),
+        _ => return None,
+    };
+    Some((target, column))
+}
+
+/// Split a line at a `#` starting a comment, outside quotes
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '#') if previous.is_whitespace() => {
+                return (&line[..index], Some(&line[index + 1..]))
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    (line, None)
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives_in_comments() {
+        assert_eq!(
+            Directive::parse(" dbtective: ignore=TEST001,DOC001 reason=\"legacy model\""),
+            Some(Directive {
+                rules: vec!["TEST001".to_string(), "DOC001".to_string()],
+                reason: Some("legacy model".to_string()),
+            })
+        );
+        assert_eq!(
+            Directive::parse("dbtective: ignore"),
+            Some(Directive {
+                rules: Vec::new(),
+                reason: None
+            })
+        );
+        assert_eq!(Directive::parse("dbtective: ignored"), None);
+        assert_eq!(Directive::parse("ignore=DOC001"), None);
+
+        let sql = "-- dbtective: ignore=TEST001 reason=\"legacy\"\nselect 1 -- dbtective: ignore\n";
+        let suppressions = sql_suppressions(Path::new("models/orders.sql"), sql);
+        assert_eq!(suppressions.len(), 2);
+        assert_eq!(suppressions[0].reason.as_deref(), Some("legacy"));
+        assert_eq!(suppressions[1].location, "models/orders.sql:2");
+
+        let yaml = r#"
+version: 2
+models:
+  # dbtective: ignore=DOC001 reason="generated"
+  - name: orders
+    description: "Orders # not a comment"
+    columns:
+      - name: order_id  # dbtective: ignore=TEST003
+      - name: status
+        # dbtective: ignore=DOC001
+  - name: customers
+    # dbtective: ignore
+sources:
+  - name: raw  # dbtective: ignore=DOC001
+    tables:
+      - name: payments
+        columns:
+          - name: id
+            # dbtective: ignore=TEST003
+"#;
+        let targets: Vec<(YamlTarget, Option<String>, usize)> =
+            yaml_suppressions(Path::new("models/schema.yml"), yaml)
+                .into_iter()
+                .map(|(target, suppression)| {
+                    let line = suppression.location.rsplit(':').next().unwrap().parse();
+                    (target, suppression.column, line.unwrap())
+                })
+                .collect();
+        let resource = |name: &str| YamlTarget::Resource(name.to_string());
+        assert_eq!(
+            targets,
+            [
+                (resource("orders"), None, 4),
+                (resource("orders"), Some("order_id".to_string()), 8),
+                (resource("orders"), Some("status".to_string()), 10),
+                (resource("customers"), None, 12),
+                (YamlTarget::Source("raw".to_string()), None, 14),
+                (resource("raw.payments"), Some("id".to_string()), 19),
+            ]
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use serde_yaml::Value;
+
+/// An argument expression of a Jinja call such as `config(...)` or `ref(...)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    List(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
+    Call(Call),
+    /// Anything we don't understand (operators, filters, variables), kept as written
+    Raw(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Call {
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub kwargs: Vec<(String, Expr)>,
+}
+
+impl Call {
+    /// Get an argument by keyword, falling back to its position
+    pub fn arg(&self, position: usize, keyword: &str) -> Option<&Expr> {
+        self.kwargs
+            .iter()
+            .find(|(key, _)| key == keyword)
+            .map(|(_, expr)| expr)
+            .or_else(|| self.args.get(position))
+    }
+}
+
+impl Expr {
+    /// The literal string value of this expression, if it is one
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Expr::Literal(Value::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Convert the expression to a YAML value. Nested calls are handed to
+    /// `resolve_call`; anything that can't be resolved yields `None` and is
+    /// left out of enclosing lists and dicts.
+    pub fn to_value(&self, resolve_call: &mut dyn FnMut(&Call) -> Option<Value>) -> Option<Value> {
+        match self {
+            Expr::Literal(value) => Some(value.clone()),
+            Expr::List(items) => Some(Value::Sequence(
+                items
+                    .iter()
+                    .filter_map(|item| item.to_value(resolve_call))
+                    .collect(),
+            )),
+            Expr::Dict(entries) => Some(Value::Mapping(
+                entries
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        Some((key.to_value(resolve_call)?, value.to_value(resolve_call)?))
+                    })
+                    .collect(),
+            )),
+            Expr::Call(call) => resolve_call(call),
+            Expr::Raw(_) => None,
+        }
+    }
+}
+
+/// Find all calls to `name` inside the Jinja tags (`{{ }}` and `{% %}`) of a file
+pub fn find_calls(content: &str, name: &str) -> Vec<Call> {
+    jinja_segments(content)
+        .into_iter()
+        .flat_map(|segment| find_calls_in(segment, name))
+        .collect()
+}
+
+fn jinja_segments(content: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("{{").into_iter().chain(rest.find("{%")).min() {
+        let close = if rest[start..].starts_with("{{") {
+            "}}"
+        } else {
+            "%}"
+        };
+        let body = &rest[start + 2..];
+        match find_tag_end(body, close) {
+            Some(end) => {
+                segments.push(&body[..end]);
+                rest = &body[end + 2..];
+            }
+            None => {
+                segments.push(body);
+                break;
+            }
+        }
+    }
+
+    segments
+}
+
+/// Find the closing delimiter of a tag, skipping over strings and nested
+/// braces so that `{{ config(meta={'a': {'b': 1}}) }}` isn't cut short
+pub(crate) fn find_tag_end(body: &str, close: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut chars = body.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if depth == 0 && body[i..].starts_with(close) => return Some(i),
+            None if c == '{' => depth += 1,
+            None if c == '}' => depth = depth.saturating_sub(1),
+            None => {}
+        }
+    }
+
+    None
+}
+
+fn find_calls_in(segment: &str, name: &str) -> Vec<Call> {
+    let mut calls = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(found) = segment[search_from..].find(name) {
+        let start = search_from + found;
+        let end = start + name.len();
+        search_from = end;
+
+        let preceded_by_ident = segment[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if preceded_by_ident {
+            continue;
+        }
+
+        let mut parser = Parser::new(&segment[end..]);
+        parser.skip_whitespace();
+        if parser.peek() != Some('(') {
+            continue;
+        }
+        parser.advance();
+
+        // An unterminated call, e.g. with an unclosed string, is skipped and
+        // the search goes on right after its name
+        let Some((args, kwargs)) = parser.parse_arguments(')') else {
+            continue;
+        };
+        search_from = end + parser.byte_offset();
+        calls.push(Call {
+            name: name.to_string(),
+            args,
+            kwargs,
+        });
+    }
+
+    calls
+}
+
+/// Parse a comma-separated argument list such as `materialized='table', tags=['a']`
+pub fn parse_arguments(text: &str) -> (Vec<Expr>, Vec<(String, Expr)>) {
+    Parser::new(text).parse_arguments(')').unwrap_or_default()
+}
+
+/// Positional and keyword arguments
+type Arguments = (Vec<Expr>, Vec<(String, Expr)>);
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn byte_offset(&self) -> usize {
+        self.chars[..self.pos].iter().map(|c| c.len_utf8()).sum()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Consume the next character. Stays put at the end of the text.
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn text(&self, start: usize) -> String {
+        self.chars[start..self.pos.min(self.chars.len())]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    /// Parse arguments up to and including the closing bracket, or `None`
+    /// if the text ends first
+    fn parse_arguments(&mut self, close: char) -> Option<Arguments> {
+        let mut args = Vec::new();
+        let mut kwargs = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return None,
+                Some(c) if c == close => {
+                    self.advance();
+                    return Some((args, kwargs));
+                }
+                Some(',') => {
+                    self.advance();
+                    continue;
+                }
+                _ => {}
+            }
+
+            match self.parse_keyword() {
+                Some(keyword) => kwargs.push((keyword, self.parse_expr(close))),
+                None => args.push(self.parse_expr(close)),
+            }
+        }
+    }
+
+    /// Consume `name=` if present (but not `name==`)
+    fn parse_keyword(&mut self) -> Option<String> {
+        let start = self.pos;
+        let ident = self.parse_ident();
+        self.skip_whitespace();
+
+        if !ident.is_empty()
+            && self.peek() == Some('=')
+            && self.chars.get(self.pos + 1) != Some(&'=')
+        {
+            self.advance();
+            return Some(ident);
+        }
+
+        self.pos = start;
+        None
+    }
+
+    fn parse_ident(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Parse a single expression. Anything that isn't a plain literal, list,
+    /// dict or call is captured as raw text up to the next separator.
+    fn parse_expr(&mut self, close: char) -> Expr {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        if let Some(expr) = self.parse_primary() {
+            self.skip_whitespace();
+            if matches!(self.peek(), Some(c) if c == ',' || c == close || c == ':')
+                || self.peek().is_none()
+            {
+                return expr;
+            }
+        }
+
+        self.pos = start;
+        self.skip_raw(close);
+        Expr::Raw(self.text(start))
+    }
+
+    fn parse_primary(&mut self) -> Option<Expr> {
+        match self.peek()? {
+            '\'' | '"' => self.parse_string().map(|s| Expr::Literal(Value::String(s))),
+            '[' => {
+                self.advance();
+                let (items, _) = self.parse_arguments(']')?;
+                Some(Expr::List(items))
+            }
+            '(' => {
+                self.advance();
+                let (items, _) = self.parse_arguments(')')?;
+                Some(Expr::List(items))
+            }
+            '{' => self.parse_dict(),
+            c if c.is_ascii_digit() || c == '-' => self.parse_number(),
+            c if c.is_alphabetic() || c == '_' => self.parse_ident_or_call(),
+            _ => None,
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        let quote = self.advance()?;
+        let mut value = String::new();
+
+        loop {
+            match self.advance()? {
+                '\\' => value.push(self.advance()?),
+                c if c == quote => return Some(value),
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Option<Expr> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.advance();
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == '_')
+        {
+            self.advance();
+        }
+
+        let text = self.text(start).replace('_', "");
+        if let Ok(int) = text.parse::<i64>() {
+            Some(Expr::Literal(Value::Number(int.into())))
+        } else {
+            text.parse::<f64>()
+                .ok()
+                .map(|float| Expr::Literal(Value::Number(float.into())))
+        }
+    }
+
+    fn parse_ident_or_call(&mut self) -> Option<Expr> {
+        let name = self.parse_ident();
+        self.skip_whitespace();
+
+        if self.peek() == Some('(') {
+            self.advance();
+            let (args, kwargs) = self.parse_arguments(')')?;
+            return Some(Expr::Call(Call { name, args, kwargs }));
+        }
+
+        match name.as_str() {
+            "True" | "true" => Some(Expr::Literal(Value::Bool(true))),
+            "False" | "false" => Some(Expr::Literal(Value::Bool(false))),
+            "None" | "none" => Some(Expr::Literal(Value::Null)),
+            _ => Some(Expr::Raw(name)),
+        }
+    }
+
+    fn parse_dict(&mut self) -> Option<Expr> {
+        self.advance();
+        let mut entries = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                '}' => {
+                    self.advance();
+                    return Some(Expr::Dict(entries));
+                }
+                ',' => {
+                    self.advance();
+                }
+                _ => {
+                    let key = self.parse_expr('}');
+                    self.skip_whitespace();
+                    if self.peek() != Some(':') {
+                        return None;
+                    }
+                    self.advance();
+                    let value = self.parse_expr('}');
+                    entries.push((key, value));
+                }
+            }
+        }
+    }
+
+    /// Skip to the next top-level `,` or the closing bracket, respecting nesting and strings
+    fn skip_raw(&mut self, close: char) {
+        let mut depth = 0usize;
+
+        while let Some(c) = self.peek() {
+            match c {
+                '\'' | '"' => {
+                    self.parse_string();
+                    continue;
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth > 0 => depth -= 1,
+                c if depth == 0 && (c == ',' || c == close) => break,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(value: &str) -> Expr {
+        Expr::Literal(Value::String(value.to_string()))
+    }
+
+    #[test]
+    fn test_parse_literal_arguments() {
+        let (args, kwargs) = parse_arguments(
+            r#"'positional', materialized="table", enabled=True, threads=4, tags=['a', "b"])"#,
+        );
+
+        assert_eq!(args, vec![literal("positional")]);
+        assert_eq!(kwargs[0], ("materialized".to_string(), literal("table")));
+        assert_eq!(kwargs[1].1, Expr::Literal(Value::Bool(true)));
+        assert_eq!(kwargs[2].1, Expr::Literal(Value::Number(4.into())));
+        assert_eq!(kwargs[3].1, Expr::List(vec![literal("a"), literal("b")]));
+    }
+
+    #[test]
+    fn test_parse_nested_dict() {
+        let (_, kwargs) =
+            parse_arguments("meta={'owner': {'team': 'finance', 'email': 'x@y.z'}, 'tier': 1})");
+
+        let value = kwargs[0].1.to_value(&mut |_| None).unwrap();
+        assert_eq!(value["owner"]["email"], Value::String("x@y.z".to_string()));
+        assert_eq!(value["tier"], Value::Number(1.into()));
+    }
+
+    #[test]
+    fn test_unparseable_expressions_are_raw() {
+        let (_, kwargs) =
+            parse_arguments("alias='a' ~ suffix, schema=target.schema, unique_key='id')");
+
+        assert_eq!(kwargs[0].1, Expr::Raw("'a' ~ suffix".to_string()));
+        assert_eq!(kwargs[1].1, Expr::Raw("target.schema".to_string()));
+        assert_eq!(kwargs[2].1, literal("id"));
+    }
+
+    #[test]
+    fn test_find_calls_in_jinja_tags() {
+        let sql = r#"
+        {{ config(materialized='view') }}
+        -- the word ref( outside of jinja is ignored
+        select * from {{ ref('orders') }}
+        join {{ ref('shop', 'customers') }} using (id)
+        join {{ source('raw', 'payments') }} using (id)
+        "#;
+
+        let configs = find_calls("{{ config(meta={'a': {'b': '}}'}}) }}", "config");
+        let meta = configs[0].kwargs[0].1.to_value(&mut |_| None).unwrap();
+        assert_eq!(meta["a"]["b"], Value::from("}}"));
+
+        let refs = find_calls(sql, "ref");
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].args, vec![literal("orders")]);
+        assert_eq!(refs[1].args, vec![literal("shop"), literal("customers")]);
+
+        let sources = find_calls(sql, "source");
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].arg(1, "table_name"), Some(&literal("payments")));
+
+        assert!(find_calls("select * from {{ ref('x) }}", "ref").is_empty());
+        let refs = find_calls("{{ ref('x) }} join {{ ref('customers') }}", "ref");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].args, vec![literal("customers")]);
+    }
+}
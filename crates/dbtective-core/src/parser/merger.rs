@@ -1,111 +1,213 @@
+use crate::error::Result;
 use crate::parser::{sql::SqlResourceInfo, yaml::YamlResourceConfig};
-use crate::types::dbt::{DbtResource, Model, ResourceType, Source};
-use anyhow::Result;
-use std::collections::HashMap;
+use crate::types::dbt::{
+    Column, Constraint, DataTest, DbtNode, DbtProject, Exposure, Macro, Model, NodeId,
+    ResourceType, Seed, SingularTest, Snapshot, Source,
+};
+use crate::types::suppression::Suppression;
+use serde_yaml::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+/// Names passed to `ref()` and (source, table) pairs passed to `source()`
+type References<'a> = (&'a [String], &'a [(String, String)]);
 
 pub fn merge_configurations(
+    package_name: &str,
     sql_resources: HashMap<String, SqlResourceInfo>,
     yaml_configs: HashMap<String, YamlResourceConfig>,
-) -> Result<Vec<Box<dyn DbtResource>>> {
-    let mut resources = Vec::new();
-
-    // Get all unique resource names from both sources
-    let mut all_names = std::collections::HashSet::new();
-    all_names.extend(sql_resources.keys().cloned());
-    all_names.extend(yaml_configs.keys().cloned());
+    seed_files: HashMap<String, PathBuf>,
+    singular_tests: Vec<SqlResourceInfo>,
+) -> Result<DbtProject> {
+    let mut project = DbtProject::new(package_name.to_string());
+    let mut lineage: Vec<(NodeId, References)> = Vec::new();
+
+    // Get all unique resource names from both sources, sorted for a stable node order
+    let mut all_names = BTreeSet::new();
+    all_names.extend(sql_resources.keys());
+    all_names.extend(yaml_configs.keys());
+    all_names.extend(seed_files.keys());
 
     for name in all_names {
-        let sql_info = sql_resources.get(&name);
-        let yaml_config = yaml_configs.get(&name);
+        let sql_info = sql_resources.get(name);
+        let yaml_config = yaml_configs.get(name);
 
-        match (sql_info, yaml_config) {
+        let mut node = match (sql_info, yaml_config) {
             // Resource has both SQL and YAML config
-            (Some(sql), Some(yaml)) => {
-                let merged = merge_sql_and_yaml_config(sql, yaml)?;
-                resources.push(merged);
-            }
+            (Some(sql), Some(yaml)) => merge_sql_and_yaml_config(sql, yaml),
             // Resource only has SQL config
-            (Some(sql), None) => {
-                let resource = create_resource_from_sql_only(sql)?;
-                resources.push(resource);
-            }
+            (Some(sql), None) => create_resource_from_sql_only(sql),
             // Resource only has YAML config (e.g., sources)
-            (None, Some(yaml)) => {
-                let resource = create_resource_from_yaml_only(yaml)?;
-                resources.push(resource);
+            (None, Some(yaml)) => create_resource_from_yaml_only(yaml),
+            // Seed CSV without properties
+            (None, None) => build_node(&ResourceType::Seed, MergedProperties::named(name.clone())),
+        };
+
+        if let (Some(DbtNode::Seed(seed)), Some(path)) = (node.as_mut(), seed_files.get(name)) {
+            seed.seed_file = Some(path.clone());
+        }
+
+        if let Some(node) = node {
+            let id = project.add_node(node);
+            if let Some(sql) = sql_info {
+                lineage.push((id, (&sql.refs, &sql.sources)));
+            }
+            if let Some(exposure) = yaml_config.and_then(|yaml| yaml.exposure.as_ref()) {
+                lineage.push((id, (&exposure.refs, &exposure.sources)));
+            }
+
+            let unique_id = project.unique_id(id).to_string();
+            let comments = sql_info
+                .map(|sql| &sql.suppressions)
+                .into_iter()
+                .chain(yaml_config.map(|yaml| &yaml.suppressions))
+                .flatten();
+            for suppression in comments {
+                project.add_suppression(Suppression {
+                    unique_id: unique_id.clone(),
+                    ..suppression.clone()
+                });
             }
-            // This shouldn't happen given our logic above
-            (None, None) => unreachable!(),
         }
     }
 
-    Ok(resources)
+    // Add lineage edges once every node exists. References to resources
+    // outside the project (e.g. installed packages) are skipped.
+    for (id, (refs, sources)) in lineage {
+        for parent in references(&project, package_name, refs, sources) {
+            project.add_dependency(id, parent);
+        }
+    }
+
+    for test in singular_tests {
+        let depends_on = references(&project, package_name, &test.refs, &test.sources)
+            .into_iter()
+            .map(|id| project.unique_id(id).to_string())
+            .collect();
+        project.add_singular_test(SingularTest {
+            name: test.name,
+            file_path: test.file_path,
+            depends_on,
+        });
+    }
+
+    Ok(project)
 }
 
-fn merge_sql_and_yaml_config(
-    sql: &SqlResourceInfo,
-    yaml: &YamlResourceConfig,
-) -> Result<Box<dyn DbtResource>> {
-    match sql.resource_type {
-        ResourceType::Model => {
-            let mut model = Model::new(sql.name.clone());
+/// Nodes referenced with `ref()` or `source()`
+fn references(
+    project: &DbtProject,
+    package_name: &str,
+    refs: &[String],
+    sources: &[(String, String)],
+) -> Vec<NodeId> {
+    let refs = refs.iter().filter_map(|name| {
+        ["model", "seed", "snapshot"]
+            .iter()
+            .find_map(|kind| project.id_of(&format!("{}.{}.{}", kind, package_name, name)))
+    });
+    let sources = sources.iter().filter_map(|(source_name, table)| {
+        project.id_of(&format!(
+            "source.{}.{}.{}",
+            package_name, source_name, table
+        ))
+    });
+    refs.chain(sources).collect()
+}
 
+/// Properties shared by every resource type after merging SQL and YAML
+struct MergedProperties {
+    name: String,
+    description: Option<String>,
+    tags: Vec<String>,
+    tests: Vec<DataTest>,
+    meta: HashMap<String, Value>,
+    config: HashMap<String, Value>,
+    columns: Vec<Column>,
+    constraints: Vec<Constraint>,
+    sql_file: Option<PathBuf>,
+    yaml_file: Option<PathBuf>,
+}
+
+impl MergedProperties {
+    fn named(name: String) -> Self {
+        Self {
+            name,
+            description: None,
+            tags: Vec::new(),
+            tests: Vec::new(),
+            meta: HashMap::new(),
+            config: HashMap::new(),
+            columns: Vec::new(),
+            constraints: Vec::new(),
+            sql_file: None,
+            yaml_file: None,
+        }
+    }
+}
+
+fn merge_sql_and_yaml_config(sql: &SqlResourceInfo, yaml: &YamlResourceConfig) -> Option<DbtNode> {
+    match sql.resource_type {
+        // Sources typically only have YAML config, but handle edge cases
+        ResourceType::Source => create_source_table_from_configs(sql, yaml),
+        _ => {
             // YAML description takes precedence over SQL description
-            model.description = yaml
+            let description = yaml
                 .description
                 .clone()
                 .or_else(|| sql.sql_config.description.clone());
 
             // Merge tags (YAML + SQL)
-            model.tags = merge_tags(&yaml.tags, &sql.sql_config.tags);
+            let tags = merge_tags(&yaml.tags, &sql.sql_config.tags);
 
-            // YAML tests take precedence
-            model.tests = yaml.tests.clone();
+            // Merge meta (SQL config() takes precedence, like the rest of the config)
+            let meta = merge_meta(&yaml.meta, &sql.sql_config.meta);
 
-            // Merge meta (YAML takes precedence for conflicts)
-            model.meta = merge_meta(&yaml.meta, &sql.sql_config.meta);
-
-            // SQL-specific config
-            model.materialized = sql.sql_config.materialized.clone();
-            model.sql_file = Some(sql.file_path.clone());
-            model.yaml_file = Some(yaml.file_path.clone());
+            // SQL config() takes precedence over the YAML config block
+            let mut config = merge_config(&yaml.config, &sql.sql_config.config);
+            if config.contains_key("tags") {
+                config.insert("tags".to_string(), tags_value(&tags));
+            }
+            if config.contains_key("meta") {
+                config.insert("meta".to_string(), meta_value(&meta));
+            }
 
-            Ok(Box::new(model))
-        }
-        ResourceType::Source => {
-            // Sources typically only have YAML config, but handle edge cases
-            create_source_table_from_configs(sql, yaml)
-        }
-        _ => {
-            // TODO: Handle other resource types (snapshots, seeds, etc.)
-            create_resource_from_sql_only(sql)
+            let properties = MergedProperties {
+                name: sql.name.clone(),
+                description,
+                tags,
+                // YAML tests take precedence
+                tests: yaml.tests.clone(),
+                meta,
+                config,
+                // Columns are only documented in YAML
+                columns: yaml.columns.clone(),
+                constraints: yaml.constraints.clone(),
+                sql_file: Some(sql.file_path.clone()),
+                yaml_file: Some(yaml.file_path.clone()),
+            };
+            build_node(&sql.resource_type, properties)
         }
     }
 }
 
-fn create_resource_from_sql_only(sql: &SqlResourceInfo) -> Result<Box<dyn DbtResource>> {
-    match sql.resource_type {
-        ResourceType::Model => {
-            let mut model = Model::new(sql.name.clone());
-            model.description = sql.sql_config.description.clone();
-            model.tags = sql.sql_config.tags.clone();
-            model.meta = sql.sql_config.meta.clone();
-            model.materialized = sql.sql_config.materialized.clone();
-            model.sql_file = Some(sql.file_path.clone());
-
-            Ok(Box::new(model))
-        }
-        _ => {
-            // For now, treat other types as models
-            create_resource_from_sql_only(&SqlResourceInfo {
-                resource_type: ResourceType::Model,
-                ..sql.clone()
-            })
-        }
-    }
+fn create_resource_from_sql_only(sql: &SqlResourceInfo) -> Option<DbtNode> {
+    let properties = MergedProperties {
+        name: sql.name.clone(),
+        description: sql.sql_config.description.clone(),
+        tags: sql.sql_config.tags.clone(),
+        tests: Vec::new(),
+        meta: sql.sql_config.meta.clone(),
+        config: sql.sql_config.config.clone(),
+        columns: Vec::new(),
+        constraints: Vec::new(),
+        sql_file: Some(sql.file_path.clone()),
+        yaml_file: None,
+    };
+    build_node(&sql.resource_type, properties)
 }
 
-fn create_resource_from_yaml_only(yaml: &YamlResourceConfig) -> Result<Box<dyn DbtResource>> {
+fn create_resource_from_yaml_only(yaml: &YamlResourceConfig) -> Option<DbtNode> {
     match yaml.resource_type {
         ResourceType::Source => {
             let mut source_table = Source::new(
@@ -116,34 +218,50 @@ fn create_resource_from_yaml_only(yaml: &YamlResourceConfig) -> Result<Box<dyn D
             source_table.tags = yaml.tags.clone();
             source_table.tests = yaml.tests.clone();
             source_table.meta = yaml.meta.clone();
-            source_table.yaml_file = Some(yaml.file_path.to_string_lossy().to_string());
+            source_table.config = yaml.config.clone();
+            source_table.columns = yaml.columns.clone();
+            source_table.yaml_file = Some(yaml.file_path.clone());
 
-            Ok(Box::new(source_table))
+            Some(DbtNode::Source(source_table))
         }
-        ResourceType::Model => {
-            let mut model = Model::new(yaml.name.clone());
-            model.description = yaml.description.clone();
-            model.tags = yaml.tags.clone();
-            model.tests = yaml.tests.clone();
-            model.meta = yaml.meta.clone();
-            model.yaml_file = Some(yaml.file_path.clone());
-
-            Ok(Box::new(model))
+        ResourceType::Exposure => {
+            let mut exposure = Exposure::new(yaml.name.clone());
+            exposure.description = yaml.description.clone();
+            exposure.tags = yaml.tags.clone();
+            exposure.meta = yaml.meta.clone();
+            exposure.config = yaml.config.clone();
+            exposure.yaml_file = Some(yaml.file_path.clone());
+            if let Some(details) = &yaml.exposure {
+                exposure.exposure_type = details.exposure_type.clone();
+                exposure.owner = details.owner.clone();
+                exposure.maturity = details.maturity.clone();
+                exposure.url = details.url.clone();
+            }
+
+            Some(DbtNode::Exposure(exposure))
         }
         _ => {
-            // TODO: Handle other resource types
-            create_resource_from_yaml_only(&YamlResourceConfig {
-                resource_type: ResourceType::Model,
-                ..yaml.clone()
-            })
+            let properties = MergedProperties {
+                name: yaml.name.clone(),
+                description: yaml.description.clone(),
+                tags: yaml.tags.clone(),
+                tests: yaml.tests.clone(),
+                meta: yaml.meta.clone(),
+                config: yaml.config.clone(),
+                columns: yaml.columns.clone(),
+                constraints: yaml.constraints.clone(),
+                sql_file: None,
+                yaml_file: Some(yaml.file_path.clone()),
+            };
+            build_node(&yaml.resource_type, properties)
         }
     }
 }
 
 fn create_source_table_from_configs(
-    sql: &SqlResourceInfo,
+    _sql: &SqlResourceInfo,
     yaml: &YamlResourceConfig,
-) -> Result<Box<dyn DbtResource>> {
+) -> Option<DbtNode> {
     let mut source_table = Source::new(
         yaml.name.clone(),
         yaml.source_name.clone().unwrap_or_default(),
@@ -154,8 +272,63 @@ fn create_source_table_from_configs(
     source_table.tags = yaml.tags.clone();
     source_table.tests = yaml.tests.clone();
     source_table.meta = yaml.meta.clone();
-    source_table.yaml_file = Some(yaml.file_path.to_string_lossy().to_string());
-    Ok(Box::new(source_table))
+    source_table.config = yaml.config.clone();
+    source_table.columns = yaml.columns.clone();
+    source_table.yaml_file = Some(yaml.file_path.clone());
+    Some(DbtNode::Source(source_table))
+}
+
+fn build_node(resource_type: &ResourceType, properties: MergedProperties) -> Option<DbtNode> {
+    match resource_type {
+        ResourceType::Seed => {
+            let mut seed = Seed::new(properties.name);
+            seed.description = properties.description;
+            seed.tags = properties.tags;
+            seed.tests = properties.tests;
+            seed.meta = properties.meta;
+            seed.config = properties.config;
+            seed.columns = properties.columns;
+            seed.yaml_file = properties.yaml_file;
+            Some(DbtNode::Seed(seed))
+        }
+        ResourceType::Snapshot => {
+            let mut snapshot = Snapshot::new(properties.name);
+            snapshot.description = properties.description;
+            snapshot.tags = properties.tags;
+            snapshot.tests = properties.tests;
+            snapshot.meta = properties.meta;
+            snapshot.config = properties.config;
+            snapshot.columns = properties.columns;
+            snapshot.sql_file = properties.sql_file;
+            snapshot.yaml_file = properties.yaml_file;
+            Some(DbtNode::Snapshot(snapshot))
+        }
+        ResourceType::Macro => {
+            let mut macro_node = Macro::new(properties.name);
+            macro_node.description = properties.description;
+            macro_node.tags = properties.tags;
+            macro_node.meta = properties.meta;
+            macro_node.config = properties.config;
+            macro_node.sql_file = properties.sql_file;
+            macro_node.yaml_file = properties.yaml_file;
+            Some(DbtNode::Macro(macro_node))
+        }
+        _ => {
+            // Treat everything else as a model
+            let mut model = Model::new(properties.name);
+            model.description = properties.description;
+            model.tags = properties.tags;
+            model.tests = properties.tests;
+            model.meta = properties.meta;
+            model.materialized = config_str(&properties.config, "materialized");
+            model.config = properties.config;
+            model.columns = properties.columns;
+            model.constraints = properties.constraints;
+            model.sql_file = properties.sql_file;
+            model.yaml_file = properties.yaml_file;
+            Some(DbtNode::Model(model))
+        }
+    }
 }
 
 fn merge_tags(yaml_tags: &[String], sql_tags: &[String]) -> Vec<String> {
@@ -172,21 +345,49 @@ fn merge_tags(yaml_tags: &[String], sql_tags: &[String]) -> Vec<String> {
 }
 
 fn merge_meta(
-    yaml_meta: &HashMap<String, String>,
-    sql_meta: &HashMap<String, String>,
-) -> HashMap<String, String> {
-    let mut meta = sql_meta.clone();
+    yaml_meta: &HashMap<String, Value>,
+    sql_meta: &HashMap<String, Value>,
+) -> HashMap<String, Value> {
+    let mut meta = yaml_meta.clone();
 
-    // YAML meta takes precedence for conflicts
-    meta.extend(yaml_meta.clone());
+    // SQL config() meta takes precedence for conflicts, like in dbt. The YAML
+    // meta already has `config.meta` over top-level `meta`.
+    meta.extend(sql_meta.clone());
 
     meta
 }
 
+fn merge_config(
+    yaml_config: &HashMap<String, Value>,
+    sql_config: &HashMap<String, Value>,
+) -> HashMap<String, Value> {
+    let mut config = yaml_config.clone();
+
+    // SQL config() takes precedence for conflicts, like in dbt
+    config.extend(sql_config.clone());
+
+    config
+}
+
+fn config_str(config: &HashMap<String, Value>, key: &str) -> Option<String> {
+    config.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+fn tags_value(tags: &[String]) -> Value {
+    Value::Sequence(tags.iter().map(|tag| Value::from(tag.as_str())).collect())
+}
+
+fn meta_value(meta: &HashMap<String, Value>) -> Value {
+    Value::Mapping(
+        meta.iter()
+            .map(|(k, v)| (Value::from(k.as_str()), v.clone()))
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     #[test]
     fn test_merge_tags() {
@@ -204,18 +405,237 @@ mod tests {
     #[test]
     fn test_merge_meta() {
         let mut yaml_meta = HashMap::new();
-        yaml_meta.insert("yaml_key".to_string(), "yaml_value".to_string());
-        yaml_meta.insert("shared_key".to_string(), "yaml_wins".to_string());
+        yaml_meta.insert("yaml_key".to_string(), Value::from("yaml_value"));
+        yaml_meta.insert("shared_key".to_string(), Value::from("yaml_loses"));
 
         let mut sql_meta = HashMap::new();
-        sql_meta.insert("sql_key".to_string(), "sql_value".to_string());
-        sql_meta.insert("shared_key".to_string(), "sql_loses".to_string());
+        sql_meta.insert("sql_key".to_string(), Value::from("sql_value"));
+        sql_meta.insert("shared_key".to_string(), Value::from("sql_wins"));
 
         let merged = merge_meta(&yaml_meta, &sql_meta);
 
         assert_eq!(merged.len(), 3);
-        assert_eq!(merged.get("yaml_key"), Some(&"yaml_value".to_string()));
-        assert_eq!(merged.get("sql_key"), Some(&"sql_value".to_string()));
-        assert_eq!(merged.get("shared_key"), Some(&"yaml_wins".to_string())); // YAML wins
+        assert_eq!(merged.get("yaml_key"), Some(&Value::from("yaml_value")));
+        assert_eq!(merged.get("sql_key"), Some(&Value::from("sql_value")));
+        assert_eq!(merged.get("shared_key"), Some(&Value::from("sql_wins"))); // SQL wins
+    }
+
+    #[test]
+    fn test_merge_config() {
+        let mut yaml_config = HashMap::new();
+        yaml_config.insert("materialized".to_string(), Value::from("view"));
+        yaml_config.insert("schema".to_string(), Value::from("marts"));
+
+        let mut sql_config = HashMap::new();
+        sql_config.insert("materialized".to_string(), Value::from("table"));
+
+        let merged = merge_config(&yaml_config, &sql_config);
+
+        assert_eq!(merged.get("materialized"), Some(&Value::from("table"))); // SQL wins
+        assert_eq!(merged.get("schema"), Some(&Value::from("marts")));
+    }
+
+    #[test]
+    fn test_merge_yaml_config_block_into_model() {
+        let sql = crate::parser::sql::parse_sql_file(
+            std::path::Path::new("models/orders.sql"),
+            "{{ config(materialized='incremental', tags=['sql_tag']) }} select 1",
+            &crate::parser::project::ResolveContext::default(),
+        )
+        .unwrap();
+
+        let schema = crate::parser::yaml::parse_schema_file(
+            r#"
+models:
+  - name: orders
+    config:
+      materialized: table
+      tags: ["yaml_tag"]
+      meta:
+        owner: finance
+      contract:
+        enforced: true
+"#,
+        )
+        .unwrap()
+        .remove(0);
+        let yaml = crate::parser::yaml::extract_resource_configs(
+            schema,
+            std::path::Path::new("models/schema.yml"),
+        )
+        .unwrap();
+
+        let project = merge_configurations(
+            "shop",
+            HashMap::from([("orders".to_string(), sql)]),
+            yaml,
+            HashMap::new(),
+            Vec::new(),
+        )
+        .unwrap();
+        let orders = project.resources().next().unwrap();
+
+        assert_eq!(
+            orders.tags(),
+            &vec!["yaml_tag".to_string(), "sql_tag".to_string()]
+        );
+        assert_eq!(orders.lookup("meta.owner"), Some(&Value::from("finance")));
+        assert_eq!(
+            orders.lookup("config.materialized"),
+            Some(&Value::from("incremental"))
+        );
+        assert_eq!(
+            orders.lookup("config.contract.enforced"),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            orders.lookup("config.tags.1"),
+            Some(&Value::from("sql_tag"))
+        );
+    }
+
+    #[test]
+    fn test_merge_builds_typed_nodes_and_lineage() {
+        let context = crate::parser::project::ResolveContext::default();
+        let parse = |path: &str, content: &str| {
+            crate::parser::sql::parse_sql_file(std::path::Path::new(path), content, &context)
+                .unwrap()
+        };
+        let sql_resources: HashMap<String, SqlResourceInfo> = [
+            parse(
+                "models/stg_orders.sql",
+                "select * from {{ source('raw', 'orders') }}",
+            ),
+            parse(
+                "models/orders.sql",
+                "select * from {{ ref('stg_orders') }} join {{ ref('country_codes') }} using (c)",
+            ),
+            parse(
+                "snapshots/orders_snapshot.sql",
+                "{% snapshot orders_snapshot %} select * from {{ ref('orders') }} {% endsnapshot %}",
+            ),
+            parse("macros/cents_to_dollars.sql", "{% macro cents_to_dollars() %}{% endmacro %}"),
+        ]
+        .into_iter()
+        .map(|info| (info.name.clone(), info))
+        .collect();
+
+        let schema = crate::parser::yaml::parse_schema_file(
+            r#"
+sources:
+  - name: raw
+    tables:
+      - name: orders
+        columns:
+          - name: id
+            tests: [unique]
+seeds:
+  - name: country_codes
+exposures:
+  - name: orders_dashboard
+    depends_on:
+      - ref('orders')
+      - source('raw', 'orders')
+"#,
+        )
+        .unwrap()
+        .remove(0);
+        let yaml_configs = crate::parser::yaml::extract_resource_configs(
+            schema,
+            std::path::Path::new("models/schema.yml"),
+        )
+        .unwrap();
+
+        let project = merge_configurations(
+            "shop",
+            sql_resources,
+            yaml_configs,
+            HashMap::from([
+                (
+                    "country_codes".to_string(),
+                    PathBuf::from("seeds/country_codes.csv"),
+                ),
+                ("holidays".to_string(), PathBuf::from("seeds/holidays.csv")),
+            ]),
+            vec![parse(
+                "tests/assert_orders_snapshotted.sql",
+                "select * from {{ ref('orders') }} left join {{ ref('orders_snapshot') }} using (id)",
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(project.len(), 8); // singular tests are not nodes
+        assert_eq!(project.models().count(), 2);
+        assert_eq!(project.macros().count(), 1);
+        assert_eq!(project.exposures().count(), 1);
+        assert_eq!(project.seeds().count(), 2);
+        assert_eq!(project.snapshots().count(), 1);
+        assert_eq!(project.sources().count(), 1);
+
+        let orders = project.id_of("model.shop.orders").unwrap();
+        let parents: Vec<&str> = project
+            .parents(orders)
+            .iter()
+            .map(|&id| project.unique_id(id))
+            .collect();
+        assert_eq!(
+            parents,
+            vec!["model.shop.stg_orders", "seed.shop.country_codes"]
+        );
+
+        let raw_orders = project.id_of("source.shop.raw.orders").unwrap();
+        let children: Vec<&str> = project
+            .children(raw_orders)
+            .iter()
+            .map(|&id| project.unique_id(id))
+            .collect();
+        assert_eq!(
+            children,
+            vec!["exposure.shop.orders_dashboard", "model.shop.stg_orders"]
+        );
+
+        let snapshot = project.id_of("snapshot.shop.orders_snapshot").unwrap();
+        assert_eq!(project.parents(snapshot), &[orders]);
+        assert_eq!(
+            project.singular_tests()[0].depends_on,
+            ["model.shop.orders", "snapshot.shop.orders_snapshot"]
+        );
+        assert_eq!(project.singular_tests_of("model.shop.orders").count(), 1);
+
+        let orders = project.node(orders).as_resource();
+        assert_eq!(
+            orders.depends_on(),
+            &vec![
+                "model.shop.stg_orders".to_string(),
+                "seed.shop.country_codes".to_string()
+            ]
+        );
+        assert_eq!(
+            orders.original_file_path(),
+            Some(std::path::Path::new("models/orders.sql"))
+        );
+        assert_eq!(orders.patch_path(), None);
+
+        let country_codes = project
+            .get("seed.shop.country_codes")
+            .unwrap()
+            .as_resource();
+        assert_eq!(
+            country_codes.original_file_path(),
+            Some(std::path::Path::new("seeds/country_codes.csv"))
+        );
+        assert_eq!(
+            country_codes.patch_path(),
+            Some(std::path::Path::new("models/schema.yml"))
+        );
+        assert!(project.get("seed.shop.holidays").is_some());
+
+        let raw_orders = project.node(raw_orders).as_resource();
+        assert_eq!(raw_orders.columns()[0].name, "id");
+        assert_eq!(raw_orders.columns()[0].tests, vec![DataTest::new("unique")]);
+        assert_eq!(
+            raw_orders.original_file_path(),
+            Some(std::path::Path::new("models/schema.yml"))
+        );
     }
 }
@@ -1,70 +1,227 @@
+pub mod cache;
+pub mod comments;
+pub mod jinja;
 pub mod merger;
+pub mod project;
 pub mod sql;
 pub mod yaml;
 
-use crate::types::dbt::{DbtResource, Model, ResourceType, Source};
-use anyhow::Result;
+use crate::error::{DbtectiveError, Result};
+use crate::source::{is_ignored_dir, FileSource};
+use crate::types::dbt::{DbtProject, ResourceType};
+use crate::types::diagnostic::Diagnostic;
+use cache::{CachedParse, ParseCache};
+use comments::YamlTarget;
+use project::{ProjectConfig, ResolveContext};
+use serde_yaml::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
-pub struct DbtProjectParser {
-    project_path: PathBuf,
+/// Project-level YAML files that are not schema (properties) files
+const NON_SCHEMA_FILES: &[&str] = &[
+    "dbt_project.yml",
+    "packages.yml",
+    "package-lock.yml",
+    "dependencies.yml",
+    "selectors.yml",
+    "profiles.yml",
+];
+
+/// The parsed project, plus any problems found along the way
+pub struct ParseResult {
+    pub project: DbtProject,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+pub struct DbtProjectParser<'a> {
+    source: &'a dyn FileSource,
+    use_cache: bool,
+    vars: HashMap<String, Value>,
 }
 
-impl DbtProjectParser {
-    pub fn new(project_path: PathBuf) -> Self {
-        Self { project_path }
+impl<'a> DbtProjectParser<'a> {
+    pub fn new(source: &'a dyn FileSource) -> Self {
+        Self {
+            source,
+            use_cache: false,
+            vars: HashMap::new(),
+        }
     }
 
-    pub fn parse_project(&self) -> Result<Vec<Box<dyn DbtResource>>> {
-        let mut resources: Vec<Box<dyn DbtResource>> = Vec::new();
+    /// Vars passed on the command line, overriding those in `dbt_project.yml`
+    pub fn with_vars(mut self, vars: HashMap<String, Value>) -> Self {
+        self.vars = vars;
+        self
+    }
+
+    /// Reuse parse results from `.dbtective/cache` for unchanged files.
+    /// Only sources backed by a local directory have a cache.
+    pub fn with_cache(mut self, use_cache: bool) -> Self {
+        self.use_cache = use_cache;
+        self
+    }
+
+    pub fn parse_project(&self) -> Result<ParseResult> {
+        let files = self.source.files()?;
+        let mut cache = self.load_cache();
+        let mut diagnostics = Vec::new();
+
+        let project_config = ProjectConfig::load(self.source)?;
+        let package_name = project_config
+            .name
+            .clone()
+            .or_else(|| self.source.root_name())
+            .unwrap_or_else(|| "project".to_string());
+        let project_vars = project_config.vars_for_package(&package_name);
+        let context = ResolveContext::new(project_vars, self.vars.clone());
 
         // Step 1: Parse all SQL files to get base resources + SQL configs
-        let sql_resources = self.parse_sql_files()?;
+        let (sql_resources, singular_tests) =
+            self.parse_sql_files(&files, cache.as_mut(), &context, &mut diagnostics)?;
 
         // Step 2: Parse all YAML files to get YAML configs
-        let yaml_configs = self.parse_yaml_files()?;
+        let yaml_configs =
+            self.parse_yaml_files(&files, cache.as_mut(), &context, &mut diagnostics)?;
+
+        // The cache only saves time, so a project directory that can't be
+        // written to (a read-only checkout, say) doesn't fail the run
+        #[cfg(feature = "fs")]
+        if let Some(cache) = cache {
+            if let Err(e) = cache.save() {
+                diagnostics.push(Diagnostic::new(
+                    cache::CACHE_DIR.to_string(),
+                    format!("parse cache not saved: {}", e),
+                ));
+            }
+        }
+
+        // Step 3: Find seed CSV files, which may not have any YAML
+        let seed_files = seed_files(&files);
 
-        // Step 3: Merge SQL + YAML configs using precedence rules
-        let merged_resources = merger::merge_configurations(sql_resources, yaml_configs)?;
+        // Step 4: Merge SQL + YAML configs using precedence rules
+        let project = merger::merge_configurations(
+            &package_name,
+            sql_resources,
+            yaml_configs,
+            seed_files,
+            singular_tests,
+        )?;
 
-        Ok(merged_resources)
+        Ok(ParseResult {
+            project,
+            diagnostics,
+        })
     }
 
-    fn parse_sql_files(&self) -> Result<HashMap<String, sql::SqlResourceInfo>> {
+    #[cfg(feature = "fs")]
+    fn load_cache(&self) -> Option<ParseCache> {
+        self.source
+            .local_root()
+            .filter(|_| self.use_cache)
+            .map(ParseCache::load)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    fn load_cache(&self) -> Option<ParseCache> {
+        None
+    }
+
+    fn parse_sql_files(
+        &self,
+        files: &[PathBuf],
+        mut cache: Option<&mut ParseCache>,
+        context: &ResolveContext,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<(
+        HashMap<String, sql::SqlResourceInfo>,
+        Vec<sql::SqlResourceInfo>,
+    )> {
         let mut sql_resources = HashMap::new();
+        let mut singular_tests = Vec::new();
+
+        for relative_path in project_files(files, &["sql"]) {
+            let content = self.source.read_to_string(relative_path)?;
+            if is_generic_test(relative_path, &content) {
+                continue;
+            }
+            let key = relative_path.to_string_lossy().to_string();
 
-        for entry in WalkDir::new(&self.project_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().map_or(false, |ext| ext == "sql"))
-        {
-            let content = std::fs::read_to_string(entry.path())?;
-            let resource_info = sql::parse_sql_file(entry.path(), &content)?;
-            sql_resources.insert(resource_info.name.clone(), resource_info);
+            let mut resource_info = match cache.as_mut().and_then(|c| c.get(&key, &content)) {
+                Some(CachedParse::Sql(info)) => *info,
+                _ => {
+                    let info = sql::parse_sql_file(relative_path, &content, context)?;
+
+                    // Configs using var() or env_var() depend on more than the
+                    // file content, so they are always parsed again
+                    if let Some(cache) = cache.as_mut().filter(|_| !info.depends_on_context()) {
+                        cache.insert(&key, &content, CachedParse::Sql(Box::new(info.clone())));
+                    }
+                    info
+                }
+            };
+
+            diagnostics.append(&mut resource_info.diagnostics);
+            if is_singular_test(relative_path) {
+                singular_tests.push(resource_info);
+            } else if resource_info.resource_type == ResourceType::Macro {
+                // Keyed "macro.name", like macro properties in YAML
+                for macro_info in resource_info.into_macros() {
+                    sql_resources.insert(format!("macro.{}", macro_info.name), macro_info);
+                }
+            } else {
+                sql_resources.insert(resource_info.name.clone(), resource_info);
+            }
         }
 
-        Ok(sql_resources)
+        Ok((sql_resources, singular_tests))
     }
 
-    fn parse_yaml_files(&self) -> Result<HashMap<String, yaml::YamlResourceConfig>> {
+    fn parse_yaml_files(
+        &self,
+        files: &[PathBuf],
+        mut cache: Option<&mut ParseCache>,
+        context: &ResolveContext,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<HashMap<String, yaml::YamlResourceConfig>> {
         let mut yaml_configs = HashMap::new();
 
-        for entry in WalkDir::new(&self.project_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path()
-                    .extension()
-                    .map_or(false, |ext| ext == "yml" || ext == "yaml")
-            })
-        {
-            let content = std::fs::read_to_string(entry.path())?;
-            let schema = yaml::parse_schema_file(&content)?;
-
-            // Extract individual resource configs from the schema file
-            let resource_configs = yaml::extract_resource_configs(schema, entry.path())?;
+        for relative_path in project_files(files, &["yml", "yaml"]) {
+            let content = self.source.read_to_string(relative_path)?;
+            let key = relative_path.to_string_lossy().to_string();
+
+            let resource_configs = match cache.as_mut().and_then(|c| c.get(&key, &content)) {
+                Some(CachedParse::Yaml(configs)) => configs,
+                _ => {
+                    // Extract individual resource configs from every document in the file
+                    let mut configs = HashMap::new();
+                    let schemas = yaml::parse_schema_file(&content)
+                        .map_err(|e| DbtectiveError::yaml(relative_path, e))?;
+                    for schema in schemas {
+                        configs.extend(yaml::extract_resource_configs(schema, relative_path)?);
+                    }
+                    attach_yaml_suppressions(&mut configs, relative_path, &content);
+
+                    // Meta in `config:` shows up twice, so each problem is reported once
+                    let mut unresolved = Vec::new();
+                    for config in configs.values_mut() {
+                        config.resolve_calls(context, &mut unresolved);
+                    }
+                    unresolved.sort();
+                    unresolved.dedup();
+                    diagnostics.extend(unresolved.into_iter().map(|message| {
+                        Diagnostic::new(relative_path.to_string_lossy().to_string(), message)
+                    }));
+
+                    // Like SQL configs, ones using var() or env_var() are always parsed again
+                    if let Some(cache) = cache
+                        .as_mut()
+                        .filter(|_| !yaml::depends_on_context(&content))
+                    {
+                        cache.insert(&key, &content, CachedParse::Yaml(configs.clone()));
+                    }
+                    configs
+                }
+            };
 
             for (name, config) in resource_configs {
                 yaml_configs.insert(name, config);
@@ -74,3 +231,151 @@ impl DbtProjectParser {
         Ok(yaml_configs)
     }
 }
+
+/// Give `# dbtective: ignore` comments to the configs of the resources they're written in
+fn attach_yaml_suppressions(
+    configs: &mut HashMap<String, yaml::YamlResourceConfig>,
+    file_path: &Path,
+    content: &str,
+) {
+    // Targets use the same keys as the configs
+    for (target, suppression) in comments::yaml_suppressions(file_path, content) {
+        for (_, config) in configs.iter_mut().filter(|(key, config)| match &target {
+            YamlTarget::Resource(resource) => *key == resource,
+            YamlTarget::Source(source) => config.source_name.as_ref() == Some(source),
+        }) {
+            config.suppressions.push(suppression.clone());
+        }
+    }
+}
+
+/// Seed CSV files under a `seeds` directory, keyed by seed name
+fn seed_files(files: &[PathBuf]) -> HashMap<String, PathBuf> {
+    project_files(files, &["csv"])
+        .filter(|path| path.components().any(|c| c.as_os_str() == "seeds"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some((name, path.clone()))
+        })
+        .collect()
+}
+
+/// Project files with one of the given extensions, skipping hidden
+/// directories, build output and project-level config files
+fn project_files<'f>(
+    files: &'f [PathBuf],
+    extensions: &'f [&str],
+) -> impl Iterator<Item = &'f PathBuf> + 'f {
+    files
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext))
+        })
+        .filter(|path| !in_ignored_dir(path))
+        .filter(|path| !(path.components().count() == 1 && is_non_schema_file(path)))
+}
+
+/// SQL files in the `tests` directory. Generic tests are skipped before this.
+fn is_singular_test(path: &Path) -> bool {
+    path.components()
+        .next()
+        .is_some_and(|dir| dir.as_os_str() == "tests")
+}
+
+/// Generic test definitions: files in `tests/generic`, or files anywhere
+/// (usually `macros`) defining a `{% test name(...) %}` block. They are
+/// neither resources nor singular tests.
+fn is_generic_test(path: &Path, content: &str) -> bool {
+    let mut components = path.components().map(|c| c.as_os_str());
+    let in_tests_generic = components.next().is_some_and(|dir| dir == "tests")
+        && components.next().is_some_and(|dir| dir == "generic");
+    in_tests_generic
+        || content.match_indices("{%").any(|(start, _)| {
+            content[start + 2..]
+                .trim_start_matches('-')
+                .trim_start()
+                .strip_prefix("test")
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+        })
+}
+
+fn in_ignored_dir(path: &Path) -> bool {
+    path.parent().is_some_and(|parent| {
+        parent
+            .components()
+            .any(|c| is_ignored_dir(&c.as_os_str().to_string_lossy()))
+    })
+}
+
+fn is_non_schema_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| NON_SCHEMA_FILES.contains(&name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MemorySource;
+
+    #[test]
+    fn test_generic_tests_are_not_resources() {
+        let source = MemorySource::new()
+            .with_file("dbt_project.yml", "name: shop\n")
+            .with_file("models/orders.sql", "select 1")
+            .with_file(
+                "tests/generic/is_positive.sql",
+                "{% test is_positive(model, column_name) %} select 1 {% endtest %}",
+            )
+            .with_file(
+                "models/generic/not_empty.sql",
+                "{%- test not_empty(model) -%} select 1 {%- endtest -%}",
+            )
+            .with_file(
+                "tests/assert_orders.sql",
+                "select * from {{ ref('orders') }}",
+            );
+
+        let project = DbtProjectParser::new(&source)
+            .parse_project()
+            .unwrap()
+            .project;
+        let ids: Vec<&str> = project.resources().map(|r| r.unique_id()).collect();
+        assert_eq!(ids, ["model.shop.orders"]);
+    }
+
+    #[test]
+    fn test_vars_in_yaml_configs() {
+        let source = MemorySource::new()
+            .with_file(
+                "dbt_project.yml",
+                "name: shop\nvars:\n  mart_schema: marts\n",
+            )
+            .with_file("models/orders.sql", "select 1")
+            .with_file(
+                "models/schema.yml",
+                r#"
+models:
+  - name: orders
+    config:
+      schema: "{{ var('mart_schema') }}"
+      meta:
+        owner: "{{ env_var('DBTECTIVE_UNSET_OWNER') }}"
+"#,
+            );
+
+        let parsed = DbtProjectParser::new(&source).parse_project().unwrap();
+        let orders = parsed.project.resources().next().unwrap();
+        assert_eq!(orders.lookup("config.schema"), Some(&Value::from("marts")));
+        assert_eq!(
+            parsed
+                .diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["models/schema.yml: env_var('DBTECTIVE_UNSET_OWNER') is not set in the environment and has no default"]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::error::{DbtectiveError, Result};
+use crate::parser::jinja::{self, Call, Expr};
+use crate::source::FileSource;
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const PROJECT_FILE: &str = "dbt_project.yml";
+
+/// The parts of `dbt_project.yml` dbtective cares about
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectConfig {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub vars: HashMap<String, Value>,
+}
+
+impl ProjectConfig {
+    /// Load `dbt_project.yml` from the project root. A missing file yields an empty config.
+    pub fn load(source: &dyn FileSource) -> Result<Self> {
+        let path = Path::new(PROJECT_FILE);
+        if !source.exists(path) {
+            return Ok(Self::default());
+        }
+
+        let content = source.read_to_string(path)?;
+        Self::parse(&content).map_err(|e| DbtectiveError::config_yaml(path, e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, serde_yaml::Error> {
+        Ok(serde_yaml::from_str::<Option<Self>>(content)?.unwrap_or_default())
+    }
+
+    /// Vars visible to resources of `package`: global vars, overridden by
+    /// vars scoped under the package name (`vars: {my_package: {...}}`)
+    pub fn vars_for_package(&self, package: &str) -> HashMap<String, Value> {
+        let mut vars: HashMap<String, Value> = self
+            .vars
+            .iter()
+            .filter(|(key, _)| key.as_str() != package)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        if let Some(Value::Mapping(scoped)) = self.vars.get(package) {
+            for (key, value) in scoped {
+                if let Some(key) = key.as_str() {
+                    vars.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+
+        vars
+    }
+}
+
+/// Values available to `var()` and `env_var()` while extracting configs
+#[derive(Debug, Clone, Default)]
+pub struct ResolveContext {
+    pub vars: HashMap<String, Value>,
+    pub env: HashMap<String, String>,
+}
+
+impl ResolveContext {
+    /// Build a context from project vars, overridden by `--vars`, and the
+    /// process environment when the `env` feature is enabled
+    pub fn new(project_vars: HashMap<String, Value>, cli_vars: HashMap<String, Value>) -> Self {
+        let mut vars = project_vars;
+        vars.extend(cli_vars);
+
+        Self {
+            vars,
+            env: process_env(),
+        }
+    }
+
+    /// The value of a `var()` or `env_var()` call, or its default. Calls that
+    /// can't be resolved are described in `unresolved`.
+    pub fn resolve_call(&self, call: &Call, unresolved: &mut Vec<String>) -> Option<Value> {
+        let name = call.arg(0, "name").and_then(|expr| expr.as_str())?;
+        let value = match call.name.as_str() {
+            "var" => self.vars.get(name).cloned(),
+            "env_var" => self.env.get(name).cloned().map(Value::String),
+            _ => return None,
+        };
+
+        value
+            .or_else(|| {
+                let default = call.arg(1, "default")?;
+                default.to_value(&mut |nested| self.resolve_call(nested, unresolved))
+            })
+            .or_else(|| {
+                let source = match call.name.as_str() {
+                    "var" => "is not defined in dbt_project.yml or --vars",
+                    _ => "is not set in the environment",
+                };
+                unresolved.push(format!(
+                    "{}('{}') {} and has no default",
+                    call.name, name, source
+                ));
+                None
+            })
+    }
+
+    /// Resolve `{{ var(...) }}` and `{{ env_var(...) }}` tags in the strings
+    /// of a YAML value, like `schema: "{{ env_var('DBT_SCHEMA') }}"`. A string
+    /// that is just one tag takes the resolved value as is; other Jinja is
+    /// left as written.
+    pub fn render(&self, value: &mut Value, unresolved: &mut Vec<String>) {
+        match value {
+            Value::String(text) => {
+                if let Some(rendered) = self.render_str(text, unresolved) {
+                    *value = rendered;
+                }
+            }
+            Value::Sequence(items) => {
+                for item in items {
+                    self.render(item, unresolved);
+                }
+            }
+            Value::Mapping(mapping) => {
+                for (_, item) in mapping.iter_mut() {
+                    self.render(item, unresolved);
+                }
+            }
+            Value::Tagged(tagged) => self.render(&mut tagged.value, unresolved),
+            _ => {}
+        }
+    }
+
+    fn render_str(&self, text: &str, unresolved: &mut Vec<String>) -> Option<Value> {
+        let mut rendered = String::new();
+        let mut rest = text;
+        let mut changed = false;
+
+        while let Some(start) = rest.find("{{") {
+            let body = &rest[start + 2..];
+            let end = jinja::find_tag_end(body, "}}")?;
+            let tag = &rest[start..start + 2 + end + 2];
+            let expression = body[..end].trim_matches('-');
+            let (args, kwargs) = jinja::parse_arguments(&format!("{})", expression));
+            let value = match (args.as_slice(), kwargs.is_empty()) {
+                ([Expr::Call(call)], true) => self.resolve_call(call, unresolved),
+                _ => None,
+            };
+
+            let spliced = match value {
+                Some(value) if tag == text.trim() => return Some(value),
+                Some(Value::String(value)) => Some(value),
+                Some(Value::Number(value)) => Some(value.to_string()),
+                Some(Value::Bool(value)) => Some(value.to_string()),
+                // Lists and mappings can't be spliced into text
+                _ => None,
+            };
+            rendered.push_str(&rest[..start]);
+            changed |= spliced.is_some();
+            rendered.push_str(spliced.as_deref().unwrap_or(tag));
+            rest = &rest[start + tag.len()..];
+        }
+
+        rendered.push_str(rest);
+        changed.then_some(Value::String(rendered))
+    }
+}
+
+#[cfg(feature = "env")]
+fn process_env() -> HashMap<String, String> {
+    std::env::vars().collect()
+}
+
+/// Without an environment (e.g. in the browser) `env_var()` falls back to its default
+#[cfg(not(feature = "env"))]
+fn process_env() -> HashMap<String, String> {
+    HashMap::new()
+}
+
+/// Parse a `--vars` argument, a YAML (or JSON) mapping like `{key: value}`
+pub fn parse_cli_vars(vars: &str) -> Result<HashMap<String, Value>> {
+    let parsed: Option<HashMap<String, Value>> = serde_yaml::from_str(vars)
+        .map_err(|e| DbtectiveError::config(format!("--vars must be a YAML mapping: {}", e)))?;
+    Ok(parsed.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_scoped_vars() {
+        let config = ProjectConfig::parse(
+            r#"
+name: jaffle_shop
+version: '1.0.0'
+vars:
+  start_date: '2020-01-01'
+  mart_materialization: view
+  jaffle_shop:
+    mart_materialization: table
+  other_package:
+    some_var: 1
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.name, Some("jaffle_shop".to_string()));
+
+        let vars = config.vars_for_package("jaffle_shop");
+        assert_eq!(vars["start_date"], Value::from("2020-01-01"));
+        assert_eq!(vars["mart_materialization"], Value::from("table"));
+        assert!(!vars.contains_key("jaffle_shop"));
+    }
+
+    #[test]
+    fn test_cli_vars_override_project_vars() {
+        let project_vars = HashMap::from([("a".to_string(), Value::from(1))]);
+        let cli_vars = parse_cli_vars(r#"{"a": 2, b: three}"#).unwrap();
+
+        let context = ResolveContext::new(project_vars, cli_vars);
+        assert_eq!(context.vars["a"], Value::from(2));
+        assert_eq!(context.vars["b"], Value::from("three"));
+
+        assert!(parse_cli_vars("[1, 2]").is_err());
+        assert!(parse_cli_vars("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_render_yaml_values() {
+        let context = ResolveContext {
+            vars: HashMap::from([("retention_days".to_string(), Value::from(30))]),
+            env: HashMap::from([("DBT_SCHEMA".to_string(), "marts".to_string())]),
+        };
+        let mut value: Value = serde_yaml::from_str(
+            r#"
+schema: "{{ env_var('DBT_SCHEMA') }}"
+alias: "orders_{{ env_var('DBT_SCHEMA') }}"
+meta:
+  retention_days: "{{ var('retention_days') }}"
+  owner: "{{ env_var('DBT_OWNER', 'finance') }}"
+  tier: "{{ var('tier') }}"
+  note: "{{ this.name | upper }}"
+"#,
+        )
+        .unwrap();
+        let mut unresolved = Vec::new();
+        context.render(&mut value, &mut unresolved);
+
+        assert_eq!(value["schema"], Value::from("marts"));
+        assert_eq!(value["alias"], Value::from("orders_marts"));
+        assert_eq!(value["meta"]["retention_days"], Value::from(30));
+        assert_eq!(value["meta"]["owner"], Value::from("finance"));
+        assert_eq!(value["meta"]["tier"], Value::from("{{ var('tier') }}"));
+        assert_eq!(
+            value["meta"]["note"],
+            Value::from("{{ this.name | upper }}")
+        );
+        assert_eq!(
+            unresolved,
+            ["var('tier') is not defined in dbt_project.yml or --vars and has no default"]
+        );
+    }
+}
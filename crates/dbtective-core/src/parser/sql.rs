@@ -1,29 +1,83 @@
-use anyhow::Result;
+use crate::error::Result;
+use crate::parser::comments;
+use crate::parser::jinja;
+use crate::parser::project::ResolveContext;
+use crate::types::diagnostic::Diagnostic;
+use crate::types::suppression::Suppression;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SqlResourceInfo {
     pub name: String,
     pub file_path: std::path::PathBuf,
     pub resource_type: crate::types::dbt::ResourceType,
     pub sql_config: SqlConfig,
     pub sql_content: String,
+    pub refs: Vec<String>,              // Resources referenced with ref()
+    pub sources: Vec<(String, String)>, // (source, table) pairs referenced with source()
+    /// `-- dbtective: ignore` comments
+    #[serde(default)]
+    pub suppressions: Vec<Suppression>,
+    #[serde(skip)]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
-#[derive(Debug, Clone, Default)]
+impl SqlResourceInfo {
+    /// Whether the config uses `var()` or `env_var()`, so the parsed result
+    /// depends on more than the file content
+    pub fn depends_on_context(&self) -> bool {
+        let config_raw = &self.sql_config.config_raw;
+        !jinja::find_calls(config_raw, "var").is_empty()
+            || !jinja::find_calls(config_raw, "env_var").is_empty()
+    }
+
+    /// One resource per `{% macro name(...) %}` block of a macro file. Calls
+    /// in a macro body only run where it's used, so refs and sources are dropped.
+    pub fn into_macros(self) -> Vec<SqlResourceInfo> {
+        extract_macro_names(&self.sql_content)
+            .into_iter()
+            .map(|name| SqlResourceInfo {
+                name,
+                refs: Vec::new(),
+                sources: Vec::new(),
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SqlConfig {
     pub description: Option<String>,
     pub materialized: Option<String>,
     pub tags: Vec<String>,
-    pub meta: HashMap<String, String>,
-    pub config_raw: String, // Store the raw config block for debugging
+    pub meta: HashMap<String, Value>,
+    pub config: HashMap<String, Value>, // All config() arguments as a value tree
+    pub config_raw: String,             // Store the raw config block for debugging
 }
 
-pub fn parse_sql_file(file_path: &Path, content: &str) -> Result<SqlResourceInfo> {
-    let name = extract_resource_name_from_path(file_path);
+pub fn parse_sql_file(
+    file_path: &Path,
+    content: &str,
+    context: &ResolveContext,
+) -> Result<SqlResourceInfo> {
     let resource_type = determine_resource_type_from_path(file_path);
-    let sql_config = extract_sql_config(content)?;
+    let name = match resource_type {
+        // Snapshots are named by their {% snapshot %} block, not their file
+        crate::types::dbt::ResourceType::Snapshot => extract_snapshot_name(content),
+        _ => None,
+    }
+    .unwrap_or_else(|| extract_resource_name_from_path(file_path));
+
+    let mut unresolved = Vec::new();
+    let sql_config = extract_sql_config(content, context, &mut unresolved)?;
+    let diagnostics = unresolved
+        .into_iter()
+        .map(|message| Diagnostic::new(file_path.to_string_lossy().to_string(), message))
+        .collect();
 
     Ok(SqlResourceInfo {
         name,
@@ -31,9 +85,67 @@ pub fn parse_sql_file(file_path: &Path, content: &str) -> Result<SqlResourceInfo
         resource_type,
         sql_config,
         sql_content: content.to_string(),
+        refs: extract_refs(content),
+        sources: extract_sources(content),
+        suppressions: comments::sql_suppressions(file_path, content),
+        diagnostics,
+    })
+}
+
+fn extract_snapshot_name(content: &str) -> Option<String> {
+    content.match_indices("{%").find_map(|(start, _)| {
+        let tag = content[start + 2..].trim_start_matches('-').trim_start();
+        let name: String = tag
+            .strip_prefix("snapshot")?
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        (!name.is_empty()).then_some(name)
     })
 }
 
+pub(crate) fn extract_macro_names(content: &str) -> Vec<String> {
+    content
+        .match_indices("{%")
+        .filter_map(|(start, _)| {
+            let tag = content[start + 2..].trim_start_matches('-').trim_start();
+            let name: String = tag
+                .strip_prefix("macro")?
+                .strip_prefix(char::is_whitespace)?
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            (!name.is_empty()).then_some(name)
+        })
+        .collect()
+}
+
+pub(crate) fn extract_refs(content: &str) -> Vec<String> {
+    // ref('model') or ref('package', 'model'): the model is the last positional argument
+    let mut refs: Vec<String> = jinja::find_calls(content, "ref")
+        .iter()
+        .filter_map(|call| call.args.last().and_then(|arg| arg.as_str()))
+        .map(str::to_string)
+        .collect();
+    refs.dedup();
+    refs
+}
+
+pub(crate) fn extract_sources(content: &str) -> Vec<(String, String)> {
+    let mut sources: Vec<(String, String)> = jinja::find_calls(content, "source")
+        .iter()
+        .filter_map(|call| {
+            let source = call.arg(0, "source_name")?.as_str()?;
+            let table = call.arg(1, "table_name")?.as_str()?;
+            Some((source.to_string(), table.to_string()))
+        })
+        .collect();
+    sources.dedup();
+    sources
+}
+
 fn extract_resource_name_from_path(file_path: &Path) -> String {
     file_path
         .file_stem()
@@ -44,13 +156,13 @@ fn extract_resource_name_from_path(file_path: &Path) -> String {
 
 fn determine_resource_type_from_path(file_path: &Path) -> crate::types::dbt::ResourceType {
     // Determine type based on directory structure
-    let path_str = file_path.to_string_lossy();
+    let in_dir = |dir: &str| file_path.components().any(|c| c.as_os_str() == dir);
 
-    if path_str.contains("/models/") {
+    if in_dir("models") {
         crate::types::dbt::ResourceType::Model
-    } else if path_str.contains("/snapshots/") {
+    } else if in_dir("snapshots") {
         crate::types::dbt::ResourceType::Snapshot
-    } else if path_str.contains("/macros/") {
+    } else if in_dir("macros") {
         crate::types::dbt::ResourceType::Macro
     } else {
         // Default to model if we can't determine
@@ -58,7 +170,11 @@ fn determine_resource_type_from_path(file_path: &Path) -> crate::types::dbt::Res
     }
 }
 
-fn extract_sql_config(content: &str) -> Result<SqlConfig> {
+fn extract_sql_config(
+    content: &str,
+    context: &ResolveContext,
+    unresolved: &mut Vec<String>,
+) -> Result<SqlConfig> {
     let mut config = SqlConfig::default();
 
     // Look for {{ config(...) }} blocks
@@ -66,104 +182,86 @@ fn extract_sql_config(content: &str) -> Result<SqlConfig> {
         config.config_raw = config_block.clone();
 
         // Parse individual config items
-        config.description = extract_config_string(&config_block, "description");
-        config.materialized = extract_config_string(&config_block, "materialized");
-        config.tags = extract_config_array(&config_block, "tags");
-        // TODO: Parse meta and other complex config items
+        config.config = parse_config_block(&config_block, context, unresolved);
+        config.description = config_string(&config.config, "description");
+        config.materialized = config_string(&config.config, "materialized");
+        config.tags = config_string_list(&config.config, "tags");
+        config.meta = mapping_to_map(config.config.get("meta"));
     }
 
     Ok(config)
 }
 
 fn extract_config_block(content: &str) -> Option<String> {
-    // Find {{ config(...) }} block - this is a simplified parser
-    let mut bracket_count = 0;
-    let mut in_config = false;
-    let mut config_start = 0;
-
-    let chars: Vec<char> = content.chars().collect();
-    for (i, window) in chars.windows(10).enumerate() {
-        let window_str: String = window.iter().collect();
-
-        if window_str.starts_with("{{ config(") {
-            in_config = true;
-            config_start = i;
-            bracket_count = 2; // Start with {{
-        }
+    // Find the first {{ config(...) }} block, also accepting `{{config(` and `{{- config(`
+    let mut search_from = 0;
+    while let Some(found) = content[search_from..].find("{{") {
+        let start = search_from + found;
+        let body = &content[start + 2..];
+        let end = jinja::find_tag_end(body, "}}")?;
 
-        if in_config {
-            if chars[i] == '{' {
-                bracket_count += 1;
-            } else if chars[i] == '}' {
-                bracket_count -= 1;
-                if bracket_count == 0 {
-                    // Found the end
-                    return Some(chars[config_start..=i].iter().collect());
-                }
+        let inner = body[..end].trim_start_matches('-').trim_start();
+        if let Some(args) = inner.strip_prefix("config") {
+            if args.trim_start().starts_with('(') {
+                return Some(content[start..start + 2 + end + 2].to_string());
             }
         }
+
+        search_from = start + 2 + end + 2;
     }
 
     None
 }
 
-fn extract_config_string(config_block: &str, key: &str) -> Option<String> {
-    // Look for key='value' or key="value"
-    let pattern = format!("{}=", key);
-    if let Some(start) = config_block.find(&pattern) {
-        let remaining = &config_block[start + pattern.len()..];
-
-        // Skip whitespace
-        let remaining = remaining.trim_start();
-
-        if remaining.starts_with('"') {
-            // Double quoted string
-            if let Some(end) = remaining[1..].find('"') {
-                return Some(remaining[1..=end].to_string());
-            }
-        } else if remaining.starts_with('\'') {
-            // Single quoted string
-            if let Some(end) = remaining[1..].find('\'') {
-                return Some(remaining[1..=end].to_string());
-            }
-        }
-    }
-
-    None
+/// Parse the arguments of a `{{ config(...) }}` block into a value tree,
+/// resolving `var()` and `env_var()` calls along the way
+fn parse_config_block(
+    config_block: &str,
+    context: &ResolveContext,
+    unresolved: &mut Vec<String>,
+) -> HashMap<String, Value> {
+    jinja::find_calls(config_block, "config")
+        .into_iter()
+        .next()
+        .map(|call| {
+            call.kwargs
+                .iter()
+                .filter_map(|(key, expr)| {
+                    let value =
+                        expr.to_value(&mut |nested| context.resolve_call(nested, unresolved))?;
+                    Some((key.clone(), value))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-fn extract_config_array(config_block: &str, key: &str) -> Vec<String> {
-    // Look for key=['item1', 'item2'] or key=["item1", "item2"]
-    let pattern = format!("{}=", key);
-    if let Some(start) = config_block.find(&pattern) {
-        let remaining = &config_block[start + pattern.len()..];
-        let remaining = remaining.trim_start();
+fn config_string(config: &HashMap<String, Value>, key: &str) -> Option<String> {
+    config.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
 
-        if remaining.starts_with('[') {
-            if let Some(end) = remaining.find(']') {
-                let array_content = &remaining[1..end];
-                return parse_simple_array(array_content);
-            }
-        }
+pub(crate) fn config_string_list(config: &HashMap<String, Value>, key: &str) -> Vec<String> {
+    // dbt accepts both `tags='daily'` and `tags=['daily', 'core']`
+    match config.get(key) {
+        Some(Value::String(item)) => vec![item.clone()],
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
     }
-
-    Vec::new()
 }
 
-fn parse_simple_array(content: &str) -> Vec<String> {
-    // Simple array parser for ['item1', 'item2']
-    content
-        .split(',')
-        .map(|item| {
-            item.trim()
-                .trim_start_matches('\'')
-                .trim_end_matches('\'')
-                .trim_start_matches('"')
-                .trim_end_matches('"')
-                .to_string()
-        })
-        .filter(|item| !item.is_empty())
-        .collect()
+/// Convert a YAML mapping with string keys into a map, dropping other keys
+pub(crate) fn mapping_to_map(value: Option<&Value>) -> HashMap<String, Value> {
+    match value {
+        Some(Value::Mapping(mapping)) => mapping
+            .iter()
+            .filter_map(|(k, v)| Some((k.as_str()?.to_string(), v.clone())))
+            .collect(),
+        _ => HashMap::new(),
+    }
 }
 
 #[cfg(test)]
@@ -188,23 +286,135 @@ mod tests {
 
     #[test]
     fn test_extract_config_string() {
-        let config = r#"materialized='table', description="A test model""#;
+        let config = parse_config_block(
+            r#"{{ config(materialized='table', description="A test model") }}"#,
+            &ResolveContext::default(),
+            &mut Vec::new(),
+        );
 
         assert_eq!(
-            extract_config_string(config, "materialized"),
+            config_string(&config, "materialized"),
             Some("table".to_string())
         );
         assert_eq!(
-            extract_config_string(config, "description"),
+            config_string(&config, "description"),
             Some("A test model".to_string())
         );
     }
 
     #[test]
     fn test_extract_config_array() {
-        let config = r#"tags=['daily', 'core'], materialized='table'"#;
+        let config = parse_config_block(
+            r#"{{ config(tags=['daily', 'core'], materialized='table') }}"#,
+            &ResolveContext::default(),
+            &mut Vec::new(),
+        );
 
-        let tags = extract_config_array(config, "tags");
+        let tags = config_string_list(&config, "tags");
         assert_eq!(tags, vec!["daily", "core"]);
     }
+
+    #[test]
+    fn test_extract_structured_meta() {
+        let sql = r#"
+        {{ config(
+            materialized='incremental',
+            meta={'owner': {'team': 'finance', 'email': 'finance@example.com'}},
+            contract={'enforced': true}
+        ) }}
+        select 1
+        "#;
+
+        let config = extract_sql_config(sql, &ResolveContext::default(), &mut Vec::new()).unwrap();
+        assert_eq!(
+            config.meta["owner"]["email"],
+            Value::String("finance@example.com".to_string())
+        );
+        assert_eq!(config.config["contract"]["enforced"], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_resolve_var_and_env_var() {
+        let context = ResolveContext {
+            vars: HashMap::from([("mart_materialization".to_string(), Value::from("table"))]),
+            env: HashMap::from([("DBT_SCHEMA".to_string(), "analytics".to_string())]),
+        };
+        let sql = r#"
+        {{ config(
+            materialized=var('mart_materialization', 'view'),
+            schema=env_var('DBT_SCHEMA'),
+            alias=var('missing_alias', default='orders_v2'),
+            database=env_var('DBT_DATABASE', var('fallback_db', 'prod'))
+        ) }}
+        select 1
+        "#;
+
+        let info = parse_sql_file(Path::new("models/orders.sql"), sql, &context).unwrap();
+        let config = &info.sql_config;
+
+        assert_eq!(config.materialized, Some("table".to_string()));
+        assert_eq!(config.config["schema"], Value::from("analytics"));
+        assert_eq!(config.config["alias"], Value::from("orders_v2"));
+        assert_eq!(config.config["database"], Value::from("prod"));
+        assert!(info.diagnostics.is_empty());
+        assert!(info.depends_on_context());
+    }
+
+    #[test]
+    fn test_unresolvable_calls_are_diagnostics() {
+        let sql =
+            "{{ config(materialized=var('undefined_var'), schema=env_var('UNSET_ENV_VAR')) }}";
+
+        let info = parse_sql_file(
+            Path::new("models/orders.sql"),
+            sql,
+            &ResolveContext::default(),
+        )
+        .unwrap();
+
+        assert!(!info.sql_config.config.contains_key("materialized"));
+        assert_eq!(info.diagnostics.len(), 2);
+        assert!(info.diagnostics[0].message.contains("var('undefined_var')"));
+        assert!(info.diagnostics[1]
+            .message
+            .contains("env_var('UNSET_ENV_VAR')"));
+        assert_eq!(info.diagnostics[0].file_path, "models/orders.sql");
+        assert!(!parse_sql_file(
+            Path::new("models/a.sql"),
+            "select 1",
+            &ResolveContext::default()
+        )
+        .unwrap()
+        .depends_on_context());
+    }
+
+    #[test]
+    fn test_extract_lineage_and_snapshot_name() {
+        let sql = r#"
+        {% snapshot orders_snapshot %}
+        {{ config(strategy='timestamp', unique_key='id', updated_at='updated_at') }}
+        select * from {{ ref('stg_orders') }}
+        join {{ ref('shop', 'customers') }} using (customer_id)
+        join {{ source('raw', 'payments') }} using (order_id)
+        {% endsnapshot %}
+        "#;
+
+        let info = parse_sql_file(
+            Path::new("snapshots/orders.sql"),
+            sql,
+            &ResolveContext::default(),
+        )
+        .unwrap();
+
+        assert_eq!(info.name, "orders_snapshot");
+        assert_eq!(
+            info.resource_type,
+            crate::types::dbt::ResourceType::Snapshot
+        );
+        assert_eq!(info.refs, vec!["stg_orders", "customers"]);
+        assert_eq!(
+            info.sources,
+            vec![("raw".to_string(), "payments".to_string())]
+        );
+    }
 }
@@ -1,4 +1,9 @@
-use anyhow::Result;
+use crate::error::Result;
+use crate::parser::jinja;
+use crate::parser::project::ResolveContext;
+use crate::parser::sql::{config_string_list, extract_refs, extract_sources, mapping_to_map};
+use crate::types::dbt::{Column, Constraint, DataTest};
+use crate::types::suppression::Suppression;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -10,6 +15,8 @@ pub struct SchemaFile {
     pub sources: Option<Vec<SourceYamlConfig>>,
     pub snapshots: Option<Vec<SnapshotYamlConfig>>,
     pub seeds: Option<Vec<SeedYamlConfig>>,
+    pub exposures: Option<Vec<ExposureYamlConfig>>,
+    pub macros: Option<Vec<MacroYamlConfig>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -18,14 +25,20 @@ pub struct ModelYamlConfig {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
     pub columns: Option<Vec<ColumnYamlConfig>>,
+    #[serde(alias = "data_tests")]
     pub tests: Option<Vec<TestYamlConfig>>,
+    pub constraints: Option<Vec<ConstraintYamlConfig>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SourceYamlConfig {
     pub name: String,
     pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
     pub tables: Option<Vec<TableYamlConfig>>,
 }
 
@@ -35,7 +48,9 @@ pub struct TableYamlConfig {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
     pub columns: Option<Vec<ColumnYamlConfig>>,
+    #[serde(alias = "data_tests")]
     pub tests: Option<Vec<TestYamlConfig>>,
 }
 
@@ -45,6 +60,10 @@ pub struct SnapshotYamlConfig {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
+    pub columns: Option<Vec<ColumnYamlConfig>>,
+    #[serde(alias = "data_tests")]
+    pub tests: Option<Vec<TestYamlConfig>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -53,13 +72,48 @@ pub struct SeedYamlConfig {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
+    pub columns: Option<Vec<ColumnYamlConfig>>,
+    #[serde(alias = "data_tests")]
+    pub tests: Option<Vec<TestYamlConfig>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExposureYamlConfig {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub exposure_type: Option<String>,
+    pub owner: Option<HashMap<String, serde_yaml::Value>>,
+    pub maturity: Option<String>,
+    pub url: Option<String>,
+    /// `ref('model')` and `source('source', 'table')` strings
+    pub depends_on: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MacroYamlConfig {
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ColumnYamlConfig {
     pub name: String,
     pub description: Option<String>,
+    pub data_type: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub meta: Option<HashMap<String, serde_yaml::Value>>,
+    pub config: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(alias = "data_tests")]
     pub tests: Option<Vec<TestYamlConfig>>,
+    pub constraints: Option<Vec<ConstraintYamlConfig>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -69,21 +123,130 @@ pub enum TestYamlConfig {
     Complex(HashMap<String, serde_yaml::Value>),
 }
 
-#[derive(Debug, Clone)]
+/// `- type: primary_key` (with `columns` at model level), or just the type
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ConstraintYamlConfig {
+    Simple(String),
+    Complex {
+        #[serde(rename = "type")]
+        constraint_type: String,
+        columns: Option<Vec<String>>,
+    },
+}
+
+impl From<ConstraintYamlConfig> for Constraint {
+    fn from(constraint: ConstraintYamlConfig) -> Self {
+        match constraint {
+            ConstraintYamlConfig::Simple(constraint_type) => Constraint::new(constraint_type),
+            ConstraintYamlConfig::Complex {
+                constraint_type,
+                columns,
+            } => Constraint {
+                constraint_type,
+                columns: columns.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct YamlResourceConfig {
     pub name: String,
     pub resource_type: crate::types::dbt::ResourceType,
     pub file_path: std::path::PathBuf,
     pub description: Option<String>,
     pub tags: Vec<String>,
-    pub meta: HashMap<String, String>,
-    pub tests: Vec<String>,
+    pub meta: HashMap<String, serde_yaml::Value>,
+    pub config: HashMap<String, serde_yaml::Value>, // The `config:` block
+    pub tests: Vec<DataTest>,
+    pub columns: Vec<Column>,
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
     pub source_name: Option<String>, // For source tables
+    /// `# dbtective: ignore` comments
+    #[serde(default)]
+    pub suppressions: Vec<Suppression>,
+    /// Type, owner, maturity, url and upstream resources of an exposure
+    #[serde(default)]
+    pub exposure: Option<ExposureDetails>,
+}
+
+/// The exposure-only properties, kept until the exposure node is built
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExposureDetails {
+    pub exposure_type: Option<String>,
+    pub owner: HashMap<String, serde_yaml::Value>,
+    pub maturity: Option<String>,
+    pub url: Option<String>,
+    pub refs: Vec<String>,
+    pub sources: Vec<(String, String)>,
 }
 
-pub fn parse_schema_file(content: &str) -> Result<SchemaFile> {
-    let schema: SchemaFile = serde_yaml::from_str(content)?;
-    Ok(schema)
+impl YamlResourceConfig {
+    /// Resolve `var()` and `env_var()` tags in the config and meta of the
+    /// resource and its columns
+    pub fn resolve_calls(&mut self, context: &ResolveContext, unresolved: &mut Vec<String>) {
+        let columns_meta = self
+            .columns
+            .iter_mut()
+            .flat_map(|column| column.meta.values_mut());
+        for value in self
+            .config
+            .values_mut()
+            .chain(self.meta.values_mut())
+            .chain(columns_meta)
+        {
+            context.render(value, unresolved);
+        }
+    }
+}
+
+/// Whether a schema file uses `var()` or `env_var()`, so its parsed configs
+/// depend on more than the file content
+pub fn depends_on_context(content: &str) -> bool {
+    !jinja::find_calls(content, "var").is_empty()
+        || !jinja::find_calls(content, "env_var").is_empty()
+}
+
+/// Parse every YAML document in a schema file.
+///
+/// Anchors and aliases are resolved by serde_yaml itself; `<<` merge keys are
+/// expanded before deserializing, and empty documents are skipped.
+pub fn parse_schema_file(content: &str) -> Result<Vec<SchemaFile>, serde_yaml::Error> {
+    let mut schemas = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let mut value = serde_yaml::Value::deserialize(document)?;
+        if value.is_null() {
+            continue;
+        }
+
+        expand_merge_keys(&mut value)?;
+        schemas.push(serde_yaml::from_value(value)?);
+    }
+
+    Ok(schemas)
+}
+
+fn expand_merge_keys(value: &mut serde_yaml::Value) -> Result<(), serde_yaml::Error> {
+    // A single pass leaves behind merge keys copied in from anchors that use
+    // merge keys themselves, so repeat until none are left
+    while has_merge_key(value) {
+        value.apply_merge()?;
+    }
+    Ok(())
+}
+
+fn has_merge_key(value: &serde_yaml::Value) -> bool {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            mapping.contains_key("<<") || mapping.values().any(has_merge_key)
+        }
+        serde_yaml::Value::Sequence(sequence) => sequence.iter().any(has_merge_key),
+        serde_yaml::Value::Tagged(tagged) => has_merge_key(&tagged.value),
+        _ => false,
+    }
 }
 
 pub fn extract_resource_configs(
@@ -95,15 +258,21 @@ pub fn extract_resource_configs(
     // Extract model configs
     if let Some(models) = schema.models {
         for model in models {
+            let properties = Properties::resolve(model.tags, model.meta, model.config);
             let config = YamlResourceConfig {
                 name: model.name.clone(),
                 resource_type: crate::types::dbt::ResourceType::Model,
                 file_path: file_path.to_path_buf(),
                 description: model.description,
-                tags: model.tags.unwrap_or_default(),
-                meta: convert_meta_to_string_map(model.meta.unwrap_or_default()),
-                tests: extract_test_names(model.tests.unwrap_or_default()),
+                tags: properties.tags,
+                meta: properties.meta,
+                config: properties.config,
+                tests: extract_tests(model.tests.unwrap_or_default()),
+                columns: extract_columns(model.columns),
                 source_name: None,
+                suppressions: Vec::new(),
+                exposure: None,
+                constraints: extract_constraints(model.constraints),
             };
             configs.insert(model.name, config);
         }
@@ -112,17 +281,27 @@ pub fn extract_resource_configs(
     // Extract source table configs
     if let Some(sources) = schema.sources {
         for source in sources {
+            // Source-level properties apply to every table, tables override them
+            let source_properties = Properties::resolve(source.tags, source.meta, source.config);
+
             if let Some(tables) = source.tables {
                 for table in tables {
+                    let properties = Properties::resolve(table.tags, table.meta, table.config)
+                        .inherit(&source_properties);
                     let config = YamlResourceConfig {
                         name: table.name.clone(),
                         resource_type: crate::types::dbt::ResourceType::Source,
                         file_path: file_path.to_path_buf(),
                         description: table.description,
-                        tags: table.tags.unwrap_or_default(),
-                        meta: convert_meta_to_string_map(table.meta.unwrap_or_default()),
-                        tests: extract_test_names(table.tests.unwrap_or_default()),
+                        tags: properties.tags,
+                        meta: properties.meta,
+                        config: properties.config,
+                        tests: extract_tests(table.tests.unwrap_or_default()),
+                        columns: extract_columns(table.columns),
                         source_name: Some(source.name.clone()),
+                        suppressions: Vec::new(),
+                        exposure: None,
+                        constraints: Vec::new(),
                     };
                     // Use "source.table" as the key for source tables
                     let key = format!("{}.{}", source.name, table.name);
@@ -132,34 +311,218 @@ pub fn extract_resource_configs(
         }
     }
 
-    // TODO: Extract snapshots, seeds, etc.
+    // Extract snapshot configs
+    if let Some(snapshots) = schema.snapshots {
+        for snapshot in snapshots {
+            let properties = Properties::resolve(snapshot.tags, snapshot.meta, snapshot.config);
+            let config = YamlResourceConfig {
+                name: snapshot.name.clone(),
+                resource_type: crate::types::dbt::ResourceType::Snapshot,
+                file_path: file_path.to_path_buf(),
+                description: snapshot.description,
+                tags: properties.tags,
+                meta: properties.meta,
+                config: properties.config,
+                tests: extract_tests(snapshot.tests.unwrap_or_default()),
+                columns: extract_columns(snapshot.columns),
+                source_name: None,
+                suppressions: Vec::new(),
+                exposure: None,
+                constraints: Vec::new(),
+            };
+            configs.insert(snapshot.name, config);
+        }
+    }
+
+    // Extract seed configs
+    if let Some(seeds) = schema.seeds {
+        for seed in seeds {
+            let properties = Properties::resolve(seed.tags, seed.meta, seed.config);
+            let config = YamlResourceConfig {
+                name: seed.name.clone(),
+                resource_type: crate::types::dbt::ResourceType::Seed,
+                file_path: file_path.to_path_buf(),
+                description: seed.description,
+                tags: properties.tags,
+                meta: properties.meta,
+                config: properties.config,
+                tests: extract_tests(seed.tests.unwrap_or_default()),
+                columns: extract_columns(seed.columns),
+                source_name: None,
+                suppressions: Vec::new(),
+                exposure: None,
+                constraints: Vec::new(),
+            };
+            configs.insert(seed.name, config);
+        }
+    }
+
+    // Extract exposure configs, keyed "exposure.name" as models may share the name
+    if let Some(exposures) = schema.exposures {
+        for exposure in exposures {
+            let properties = Properties::resolve(exposure.tags, exposure.meta, exposure.config);
+            let depends_on = exposure
+                .depends_on
+                .unwrap_or_default()
+                .iter()
+                .map(|dependency| format!("{{{{ {} }}}}", dependency))
+                .collect::<String>();
+            let config = YamlResourceConfig {
+                name: exposure.name.clone(),
+                resource_type: crate::types::dbt::ResourceType::Exposure,
+                file_path: file_path.to_path_buf(),
+                description: exposure.description,
+                tags: properties.tags,
+                meta: properties.meta,
+                config: properties.config,
+                tests: Vec::new(),
+                columns: Vec::new(),
+                source_name: None,
+                suppressions: Vec::new(),
+                constraints: Vec::new(),
+                exposure: Some(ExposureDetails {
+                    exposure_type: exposure.exposure_type,
+                    owner: exposure.owner.unwrap_or_default(),
+                    maturity: exposure.maturity,
+                    url: exposure.url,
+                    refs: extract_refs(&depends_on),
+                    sources: extract_sources(&depends_on),
+                }),
+            };
+            configs.insert(format!("exposure.{}", exposure.name), config);
+        }
+    }
+
+    // Extract macro configs, keyed "macro.name" like the macros parsed from SQL
+    if let Some(macros) = schema.macros {
+        for macro_config in macros {
+            let properties =
+                Properties::resolve(macro_config.tags, macro_config.meta, macro_config.config);
+            let config = YamlResourceConfig {
+                name: macro_config.name.clone(),
+                resource_type: crate::types::dbt::ResourceType::Macro,
+                file_path: file_path.to_path_buf(),
+                description: macro_config.description,
+                tags: properties.tags,
+                meta: properties.meta,
+                config: properties.config,
+                tests: Vec::new(),
+                columns: Vec::new(),
+                source_name: None,
+                suppressions: Vec::new(),
+                constraints: Vec::new(),
+                exposure: None,
+            };
+            configs.insert(format!("macro.{}", macro_config.name), config);
+        }
+    }
 
     Ok(configs)
 }
 
-fn convert_meta_to_string_map(meta: HashMap<String, serde_yaml::Value>) -> HashMap<String, String> {
-    meta.into_iter()
-        .map(|(k, v)| (k, value_to_string(v)))
-        .collect()
+/// Tags, meta and config of a resource after combining the top-level
+/// properties with its `config:` block
+struct Properties {
+    tags: Vec<String>,
+    meta: HashMap<String, serde_yaml::Value>,
+    config: HashMap<String, serde_yaml::Value>,
 }
 
-fn value_to_string(value: serde_yaml::Value) -> String {
-    match value {
-        serde_yaml::Value::String(s) => s,
-        serde_yaml::Value::Number(n) => n.to_string(),
-        serde_yaml::Value::Bool(b) => b.to_string(),
-        _ => serde_yaml::to_string(&value).unwrap_or_default(),
+impl Properties {
+    /// Tags from both places are combined; `config.meta` wins over top-level
+    /// `meta` for the same key, as it is the style dbt recommends
+    fn resolve(
+        tags: Option<Vec<String>>,
+        meta: Option<HashMap<String, serde_yaml::Value>>,
+        config: Option<HashMap<String, serde_yaml::Value>>,
+    ) -> Self {
+        let config = config.unwrap_or_default();
+
+        let mut tags = tags.unwrap_or_default();
+        for tag in config_string_list(&config, "tags") {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let mut meta = meta.unwrap_or_default();
+        meta.extend(mapping_to_map(config.get("meta")));
+
+        Self { tags, meta, config }
+    }
+
+    /// Fill in tags, meta and config from a parent (e.g. a source for its tables)
+    fn inherit(mut self, parent: &Properties) -> Self {
+        for tag in &parent.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        for (key, value) in &parent.meta {
+            self.meta
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        for (key, value) in &parent.config {
+            self.config
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        self
     }
 }
 
-fn extract_test_names(tests: Vec<TestYamlConfig>) -> Vec<String> {
+fn extract_columns(columns: Option<Vec<ColumnYamlConfig>>) -> Vec<Column> {
+    columns
+        .unwrap_or_default()
+        .into_iter()
+        .map(|column| {
+            let properties = Properties::resolve(column.tags, column.meta, column.config);
+            Column {
+                name: column.name,
+                description: column.description,
+                data_type: column.data_type,
+                tags: properties.tags,
+                meta: properties.meta,
+                tests: extract_tests(column.tests.unwrap_or_default()),
+                constraints: extract_constraints(column.constraints),
+            }
+        })
+        .collect()
+}
+
+fn extract_constraints(constraints: Option<Vec<ConstraintYamlConfig>>) -> Vec<Constraint> {
+    constraints
+        .unwrap_or_default()
+        .into_iter()
+        .map(Constraint::from)
+        .collect()
+}
+
+/// Tests are written as a name (`- unique`), a name with arguments
+/// (`- accepted_values: {values: [...]}`, optionally nested under `arguments`)
+/// or with an explicit `test_name` next to a custom `name`
+fn extract_tests(tests: Vec<TestYamlConfig>) -> Vec<DataTest> {
     tests
         .into_iter()
-        .map(|test| match test {
-            TestYamlConfig::Simple(name) => name,
-            TestYamlConfig::Complex(map) => {
-                // For complex tests, use the first key as the test name
-                map.keys().next().unwrap_or(&"unknown".to_string()).clone()
+        .filter_map(|test| match test {
+            TestYamlConfig::Simple(name) => Some(DataTest::new(name)),
+            TestYamlConfig::Complex(mut map) => {
+                let (name, mut arguments) = match map.remove("test_name") {
+                    Some(name) => (name.as_str()?.to_string(), map),
+                    None => {
+                        let (name, arguments) = map.into_iter().next()?;
+                        (name, mapping_to_map(Some(&arguments)))
+                    }
+                };
+
+                // Drop the test's own config and custom name
+                arguments.remove("config");
+                arguments.remove("name");
+                if let Some(nested) = arguments.remove("arguments") {
+                    arguments.extend(mapping_to_map(Some(&nested)));
+                }
+                Some(DataTest { name, arguments })
             }
         })
         .collect()
@@ -186,7 +549,9 @@ models:
           - not_null
 "#;
 
-        let schema = parse_schema_file(yaml_content).unwrap();
+        let mut schemas = parse_schema_file(yaml_content).unwrap();
+        assert_eq!(schemas.len(), 1);
+        let schema = schemas.remove(0);
         assert!(schema.models.is_some());
 
         let models = schema.models.unwrap();
@@ -212,7 +577,9 @@ sources:
         description: "Raw order data"
 "#;
 
-        let schema = parse_schema_file(yaml_content).unwrap();
+        let mut schemas = parse_schema_file(yaml_content).unwrap();
+        assert_eq!(schemas.len(), 1);
+        let schema = schemas.remove(0);
         assert!(schema.sources.is_some());
 
         let sources = schema.sources.unwrap();
@@ -223,4 +590,225 @@ sources:
         assert_eq!(tables.len(), 2);
         assert_eq!(tables[0].name, "users");
     }
+
+    #[test]
+    fn test_parse_anchors_and_merge_keys() {
+        let yaml_content = r#"
+version: 2
+
+x-defaults: &model_defaults
+  tags: ["core"]
+  meta:
+    owner: data-team
+
+x-extended: &extended_defaults
+  <<: *model_defaults
+  description: "Shared description"
+
+models:
+  - name: users
+    <<: *model_defaults
+    columns:
+      - &id_column
+        name: id
+        tests:
+          - unique
+          - not_null
+  - name: orders
+    <<: *extended_defaults
+    tags: ["finance"]
+    columns:
+      - *id_column
+"#;
+
+        let schemas = parse_schema_file(yaml_content).unwrap();
+        let models = schemas[0].models.as_ref().unwrap();
+
+        assert_eq!(models[0].tags, Some(vec!["core".to_string()]));
+        assert!(models[0].meta.as_ref().unwrap().contains_key("owner"));
+
+        // Keys set on the model win over merged defaults, chained merges are expanded
+        assert_eq!(models[1].tags, Some(vec!["finance".to_string()]));
+        assert_eq!(
+            models[1].description,
+            Some("Shared description".to_string())
+        );
+        assert!(models[1].meta.as_ref().unwrap().contains_key("owner"));
+
+        let columns = models[1].columns.as_ref().unwrap();
+        assert_eq!(columns[0].name, "id");
+        assert_eq!(columns[0].tests.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_multi_document_schema() {
+        let yaml_content = r#"
+version: 2
+models:
+  - name: users
+---
+version: 2
+sources:
+  - name: raw
+    tables:
+      - name: events
+---
+"#;
+
+        let schemas = parse_schema_file(yaml_content).unwrap();
+        assert_eq!(schemas.len(), 2);
+
+        let configs: HashMap<String, YamlResourceConfig> = schemas
+            .into_iter()
+            .flat_map(|schema| extract_resource_configs(schema, Path::new("models/schema.yml")))
+            .flatten()
+            .collect();
+        assert!(configs.contains_key("users"));
+        assert!(configs.contains_key("raw.events"));
+    }
+
+    #[test]
+    fn test_extract_config_blocks() {
+        let yaml_content = r#"
+version: 2
+
+models:
+  - name: orders
+    tags: ["finance"]
+    meta:
+      owner: top-level
+      tier: 1
+    config:
+      materialized: table
+      tags: ["daily", "finance"]
+      meta:
+        owner: finance-team
+      contract:
+        enforced: true
+
+sources:
+  - name: raw
+    tags: ["raw"]
+    meta:
+      loader: fivetran
+    config:
+      enabled: true
+    tables:
+      - name: payments
+        config:
+          tags: "pii"
+          meta:
+            retention_days: 30
+
+seeds:
+  - name: country_codes
+    config:
+      column_types:
+        code: varchar(2)
+
+snapshots:
+  - name: orders_snapshot
+    description: "Order history"
+    config:
+      strategy: timestamp
+"#;
+
+        let schema = parse_schema_file(yaml_content).unwrap().remove(0);
+        let configs = extract_resource_configs(schema, Path::new("models/schema.yml")).unwrap();
+
+        let orders = &configs["orders"];
+        assert_eq!(orders.tags, vec!["finance", "daily"]);
+        assert_eq!(
+            orders.meta["owner"],
+            serde_yaml::Value::from("finance-team")
+        );
+        assert_eq!(orders.meta["tier"], serde_yaml::Value::from(1));
+        assert_eq!(
+            orders.config["materialized"],
+            serde_yaml::Value::from("table")
+        );
+        assert_eq!(
+            orders.config["contract"]["enforced"],
+            serde_yaml::Value::Bool(true)
+        );
+
+        let payments = &configs["raw.payments"];
+        assert_eq!(payments.tags, vec!["pii", "raw"]);
+        assert_eq!(payments.meta["loader"], serde_yaml::Value::from("fivetran"));
+        assert_eq!(payments.meta["retention_days"], serde_yaml::Value::from(30));
+        assert_eq!(payments.config["enabled"], serde_yaml::Value::Bool(true));
+
+        let seed = &configs["country_codes"];
+        assert_eq!(seed.resource_type, crate::types::dbt::ResourceType::Seed);
+        assert!(seed.config.contains_key("column_types"));
+
+        let snapshot = &configs["orders_snapshot"];
+        assert_eq!(
+            snapshot.resource_type,
+            crate::types::dbt::ResourceType::Snapshot
+        );
+        assert_eq!(
+            snapshot.config["strategy"],
+            serde_yaml::Value::from("timestamp")
+        );
+    }
+
+    #[test]
+    fn test_extract_tests() {
+        let yaml_content = r#"
+version: 2
+models:
+  - name: orders
+    data_tests:
+      - dbt_utils.unique_combination_of_columns:
+          combination_of_columns: [order_id, line]
+          config:
+            severity: warn
+    constraints:
+      - type: unique
+        columns: [order_id, line]
+    columns:
+      - name: order_id
+        constraints: [not_null]
+      - name: status
+        constraints:
+          - type: check
+            expression: "status != ''"
+        tests:
+          - not_null
+          - accepted_values:
+              arguments:
+                values: [placed, shipped]
+          - name: status_is_known
+            test_name: relationships
+            to: ref('statuses')
+            field: status
+"#;
+
+        let schema = parse_schema_file(yaml_content).unwrap().remove(0);
+        let configs = extract_resource_configs(schema, Path::new("models/schema.yml")).unwrap();
+        let orders = &configs["orders"];
+
+        let combination = &orders.tests[0];
+        assert!(combination.matches("unique_combination_of_columns"));
+        assert!(combination.matches("dbt_utils.unique_*"));
+        assert_eq!(combination.namespace(), Some("dbt_utils"));
+        assert_eq!(
+            orders.tests[0].arguments.keys().collect::<Vec<_>>(),
+            ["combination_of_columns"]
+        );
+
+        assert_eq!(orders.constraints[0].columns, ["order_id", "line"]);
+        assert_eq!(orders.columns[0].constraints, [Constraint::new("not_null")]);
+        assert_eq!(orders.columns[1].constraints[0].constraint_type, "check");
+
+        let tests = &orders.columns[1].tests;
+        assert_eq!(tests[0], DataTest::new("not_null"));
+        assert_eq!(
+            tests[1].arguments["values"],
+            serde_yaml::from_str::<serde_yaml::Value>("[placed, shipped]").unwrap()
+        );
+        assert_eq!(tests[2].name, "relationships");
+        assert_eq!(tests[2].arguments.len(), 2);
+    }
 }
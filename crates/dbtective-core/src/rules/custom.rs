@@ -0,0 +1,351 @@
+use super::{Rule, RuleContext, Violation};
+use crate::config::{Condition, CustomRuleConfig};
+use crate::error::{DbtectiveError, Result};
+use crate::selector::SelectionSpec;
+use crate::types::dbt::{DbtResource, ResourceType};
+use crate::types::severity::RuleSeverity;
+use regex::Regex;
+use serde_json::Value;
+
+/// A rule from `[[custom_rules]]` in the config, checking conditions on the
+/// resource's [JSON form](DbtResource::to_json)
+#[derive(Debug)]
+pub struct CustomRule {
+    config: CustomRuleConfig,
+    conditions: Vec<CompiledCondition>,
+}
+
+#[derive(Debug)]
+struct CompiledCondition {
+    condition: Condition,
+    path: Vec<Step>,
+    regex: Option<Regex>,
+}
+
+/// A segment of a field path like `columns[*].meta.owner`
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    /// `[*]`, every item of a list
+    All,
+}
+
+impl CustomRule {
+    pub fn new(config: CustomRuleConfig) -> Result<Self> {
+        let error = |message: String| {
+            DbtectiveError::config(format!("custom_rules.{}: {}", config.id, message))
+        };
+        if config.id.is_empty() {
+            return Err(DbtectiveError::config(
+                "custom_rules: every rule needs an id",
+            ));
+        }
+        if config.conditions.is_empty() {
+            return Err(error("needs at least one condition".to_string()));
+        }
+        if let Some(select) = &config.select {
+            SelectionSpec::parse_cli(&[select]).map_err(|e| error(e.to_string()))?;
+        }
+
+        let conditions = config
+            .conditions
+            .iter()
+            .map(|condition| {
+                let path = parse_path(&condition.field)
+                    .ok_or_else(|| error(format!("invalid field path '{}'", condition.field)))?;
+                let regex = condition
+                    .matches
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .map_err(|e| error(format!("invalid pattern: {}", e)))?;
+                let has_check = condition.exists.is_some()
+                    || condition.equals.is_some()
+                    || regex.is_some()
+                    || condition.min_length.is_some()
+                    || condition.one_of.is_some();
+                if !has_check {
+                    return Err(error(format!(
+                        "condition on '{}' has nothing to check",
+                        condition.field
+                    )));
+                }
+                Ok(CompiledCondition {
+                    condition: condition.clone(),
+                    path,
+                    regex,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { config, conditions })
+    }
+
+    fn message(&self, resource: &dyn DbtResource, field: &str, expectation: &str) -> String {
+        let template = self
+            .config
+            .message
+            .as_deref()
+            .unwrap_or("{resource_type} {name}: {field} {expectation}");
+        template
+            .replace("{name}", resource.name())
+            .replace("{resource_type}", resource.resource_type().as_str())
+            .replace("{unique_id}", resource.unique_id())
+            .replace("{field}", field)
+            .replace("{expectation}", expectation)
+    }
+}
+
+impl Rule for CustomRule {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn default_severity(&self) -> RuleSeverity {
+        self.config
+            .severity
+            .clone()
+            .unwrap_or(RuleSeverity::Warning)
+    }
+
+    fn resource_types(&self) -> &[ResourceType] {
+        if self.config.resource_types.is_empty() {
+            ResourceType::all()
+        } else {
+            &self.config.resource_types
+        }
+    }
+
+    fn selector(&self) -> Option<&str> {
+        self.config.select.as_deref()
+    }
+
+    fn evaluate(&self, resource: &dyn DbtResource, _: &RuleContext) -> Result<Vec<Violation>> {
+        let json = resource.to_json();
+        let mut violations = Vec::new();
+        for condition in &self.conditions {
+            for (field, value) in resolve(&json, &condition.path, String::new()) {
+                if let Some(expectation) = condition.check(value.filter(|v| !v.is_null())) {
+                    let violation = Violation::new(self.message(resource, &field, &expectation));
+                    violations.push(match column_of(&json, &field) {
+                        Some(column) => violation.with_column(column),
+                        None => violation,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+impl CompiledCondition {
+    /// What the value fails to meet, if anything
+    fn check(&self, value: Option<&Value>) -> Option<String> {
+        let condition = &self.condition;
+        match (condition.exists, value) {
+            (Some(true), None) => return Some("must be set".to_string()),
+            (Some(false), Some(_)) => return Some("must not be set".to_string()),
+            _ => {}
+        }
+
+        if let Some(expected) = &condition.equals {
+            if value != Some(expected) {
+                return Some(format!("must equal {}", display(expected)));
+            }
+        }
+        if let Some(regex) = &self.regex {
+            if !value
+                .and_then(Value::as_str)
+                .is_some_and(|value| regex.is_match(value))
+            {
+                return Some(format!("must match {}", regex.as_str()));
+            }
+        }
+        if let Some(min_length) = condition.min_length {
+            let length = match value {
+                Some(Value::String(value)) => value.trim().chars().count(),
+                Some(Value::Array(items)) => items.len(),
+                _ => 0,
+            };
+            if length < min_length {
+                return Some(format!("must have a length of at least {}", min_length));
+            }
+        }
+        if let Some(allowed) = &condition.one_of {
+            let valid = match value {
+                Some(Value::Array(items)) => items.iter().all(|item| allowed.contains(item)),
+                Some(value) => allowed.contains(value),
+                None => false,
+            };
+            if !valid {
+                let allowed: Vec<String> = allowed.iter().map(display).collect();
+                return Some(format!("must be one of {}", allowed.join(", ")));
+            }
+        }
+
+        None
+    }
+}
+
+/// Parse `meta.owner.email` or `columns[*].tests[0].name`
+fn parse_path(field: &str) -> Option<Vec<Step>> {
+    let mut steps = Vec::new();
+    for segment in field.split('.') {
+        let (key, mut rest) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if key.is_empty() {
+            return None;
+        }
+        steps.push(Step::Key(key.to_string()));
+        while !rest.is_empty() {
+            let (index, after) = rest.strip_prefix('[')?.split_once(']')?;
+            steps.push(match index {
+                "*" => Step::All,
+                index => Step::Index(index.parse().ok()?),
+            });
+            rest = after;
+        }
+    }
+    Some(steps)
+}
+
+/// Values at a parsed path, with the concrete path each was found at. A
+/// missing value is `None`; `[*]` over an empty list yields nothing.
+fn resolve<'a>(value: &'a Value, path: &[Step], field: String) -> Vec<(String, Option<&'a Value>)> {
+    let Some((step, rest)) = path.split_first() else {
+        return vec![(field, Some(value))];
+    };
+    let (next, field) = match step {
+        Step::Key(key) => (
+            value.get(key),
+            if field.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", field, key)
+            },
+        ),
+        Step::Index(index) => (value.get(index), format!("{}[{}]", field, index)),
+        Step::All => {
+            return match value.as_array() {
+                Some(items) => items
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, item)| resolve(item, rest, format!("{}[{}]", field, index)))
+                    .collect(),
+                None => vec![(format!("{}[*]", field), None)],
+            }
+        }
+    };
+    match next {
+        Some(next) => resolve(next, rest, field),
+        None => vec![(field, None)],
+    }
+}
+
+/// The name of the column a resolved path like `columns[1].description` is in
+fn column_of(json: &Value, field: &str) -> Option<String> {
+    let (index, _) = field.strip_prefix("columns[")?.split_once(']')?;
+    let column = json.get("columns")?.get(index.parse::<usize>().ok()?)?;
+    column.get("name")?.as_str().map(str::to_string)
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::rules::RuleOptions;
+    use crate::types::dbt::{Column, DbtProject, Model};
+    use std::path::Path;
+
+    #[test]
+    fn test_custom_rule_conditions() {
+        let config = Config::parse(
+            r#"
+[[custom_rules]]
+id = "MART_CONVENTIONS"
+conditions = [
+    { field = "meta.owner.email", exists = true, matches = "@example\\.com$" },
+    { field = "config.materialized", in = ["table", "incremental"] },
+    { field = "columns[*].description", min_length = 10 },
+    { field = "tags", in = ["finance", "pii"] },
+]
+
+[[custom_rules]]
+id = "PII_RETENTION"
+message = "{name} is tagged pii but has no {field}"
+severity = "error"
+select = "tag:pii"
+conditions = [{ field = "meta.retention_days", exists = true }]
+"#,
+            Path::new("dbtective.toml"),
+        )
+        .unwrap();
+        let rules = config.custom_rules().unwrap();
+        assert_eq!(rules[1].default_severity(), RuleSeverity::Error);
+        assert_eq!(rules[1].selector(), Some("tag:pii"));
+
+        let mut orders = Model::new("orders".to_string());
+        orders.meta.insert(
+            "owner".to_string(),
+            serde_yaml::from_str("{email: finance@example.com}").unwrap(),
+        );
+        orders
+            .config
+            .insert("materialized".to_string(), "view".into());
+        orders.tags = vec!["finance".to_string(), "daily".to_string()];
+        orders.columns = vec![
+            Column::new("id".to_string()).with_description("Order identifier".to_string()),
+            Column::new("total".to_string()),
+        ];
+        let project = DbtProject::new("shop".to_string());
+        let options = RuleOptions::default();
+        let context = RuleContext {
+            project: &project,
+            options: &options,
+        };
+
+        let violations = rules[0].evaluate(&orders, &context).unwrap();
+        let messages: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "model orders: config.materialized must be one of table, incremental",
+                "model orders: columns[1].description must have a length of at least 10",
+                "model orders: tags must be one of finance, pii",
+            ]
+        );
+        assert_eq!(violations[1].column.as_deref(), Some("total"));
+        assert_eq!(violations[0].column, None);
+        assert_eq!(
+            rules[1].evaluate(&orders, &context).unwrap(),
+            [Violation::new(
+                "orders is tagged pii but has no meta.retention_days".to_string()
+            )]
+        );
+
+        let invalid = |toml: &str| {
+            Config::parse(toml, Path::new("dbtective.toml"))
+                .unwrap()
+                .custom_rules()
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            invalid("[[custom_rules]]\nid = \"X\"\nconditions = [{ field = \"meta.owner\" }]\n"),
+            "dbtective.toml: custom_rules.X: condition on 'meta.owner' has nothing to check"
+        );
+        assert_eq!(
+            invalid(
+                "[[custom_rules]]\nid = \"X\"\nconditions = [{ field = \"columns[\", exists = true }]\n"
+            ),
+            "dbtective.toml: custom_rules.X: invalid field path 'columns['"
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use super::{Rule, RuleContext, RuleOptions};
+use crate::config::{Config, RuleScope};
+use crate::error::Result;
+use crate::selector::{NamedSelectors, NodeSelector, SelectionSpec};
+use crate::types::dbt::{DbtProject, DbtResource, NodeId};
+use crate::types::finding::Finding;
+use crate::types::suppression::Suppression;
+use std::collections::BTreeSet;
+
+/// Findings of a rule run, plus the counts scoring is based on
+#[derive(Debug, Clone, Default)]
+pub struct RuleRun {
+    pub findings: Vec<Finding>,
+    /// Number of (rule, resource) pairs evaluated
+    pub checks: usize,
+    /// Number of those that produced at least one finding
+    pub failed_checks: usize,
+    /// Number of findings left out by a suppression
+    pub suppressed: usize,
+    /// Suppressions of rules that ran on their resource without matching a finding
+    pub unused_suppressions: Vec<Suppression>,
+    /// Suppressions ignored because they give no reason and the config requires one
+    pub unjustified_suppressions: Vec<Suppression>,
+}
+
+/// Run every enabled rule over the enabled resources of its types accepted by
+/// `applies` and in the rule's configured scope. Findings get the configured
+/// severity, or the rule's default, unless a suppression covers them.
+pub fn run_rules(
+    project: &DbtProject,
+    rules: &[&dyn Rule],
+    config: &Config,
+    selectors: &NamedSelectors,
+    applies: &dyn Fn(&dyn DbtResource) -> bool,
+) -> Result<RuleRun> {
+    let mut run = RuleRun::default();
+    let selector = NodeSelector::new(project, selectors);
+    let resources: Vec<(NodeId, &dyn DbtResource)> = project
+        .nodes()
+        .map(|(id, node)| (id, node.as_resource()))
+        .filter(|(_, resource)| resource.enabled() && applies(*resource))
+        .collect();
+
+    let (suppressions, unjustified): (Vec<Suppression>, Vec<Suppression>) = project
+        .suppressions()
+        .iter()
+        .cloned()
+        .chain(
+            resources
+                .iter()
+                .flat_map(|(_, resource)| Suppression::from_meta(*resource)),
+        )
+        .partition(|suppression| {
+            suppression.reason.is_some() || !config.suppressions.require_reason
+        });
+    run.unjustified_suppressions = unjustified;
+    // Whether each suppression covered a rule run on its resource, and a finding
+    let mut applied = vec![false; suppressions.len()];
+    let mut used = vec![false; suppressions.len()];
+
+    for rule in rules.iter().filter(|rule| config.is_enabled(rule.id())) {
+        let severity = config
+            .severity(rule.id())
+            .unwrap_or_else(|| rule.default_severity());
+        let options = RuleOptions::resolve(
+            rule.id(),
+            &rule.options_schema(),
+            &config.options(rule.id()),
+        )?;
+        let context = RuleContext {
+            project,
+            options: &options,
+        };
+        rule.prepare(project)?;
+        let resource_types = rule.resource_types();
+        // `None` is every node
+        let rule_config = config.rule(rule.id());
+        let included = match rule_config.map(|c| c.include.as_slice()) {
+            Some(scopes) if !scopes.is_empty() => scoped(&selector, scopes)?,
+            _ => None,
+        };
+        let excluded = match rule_config.map(|c| c.exclude.as_slice()) {
+            Some(scopes) if !scopes.is_empty() => scoped(&selector, scopes)?,
+            _ => Some(BTreeSet::new()),
+        };
+        let selected = match rule.selector() {
+            Some(select) => Some(selector.select(&SelectionSpec::parse_cli(&[select])?)?),
+            None => None,
+        };
+
+        for (_, resource) in resources.iter().filter(|(id, resource)| {
+            resource_types.contains(&resource.resource_type())
+                && in_scope(&included, id)
+                && in_scope(&selected, id)
+                && !in_scope(&excluded, id)
+        }) {
+            let active: Vec<usize> = (0..suppressions.len())
+                .filter(|&i| {
+                    suppressions[i].unique_id == resource.unique_id()
+                        && suppressions[i].covers(rule.id())
+                })
+                .collect();
+            let mut violations = rule.evaluate(*resource, &context)?;
+            violations.retain(|violation| {
+                let matching: Vec<usize> = active
+                    .iter()
+                    .copied()
+                    .filter(|&i| suppressions[i].matches(violation.column.as_deref()))
+                    .collect();
+                for &i in &matching {
+                    used[i] = true;
+                }
+                if !matching.is_empty() {
+                    run.suppressed += 1;
+                }
+                matching.is_empty()
+            });
+            for &i in &active {
+                applied[i] = true;
+            }
+            run.checks += 1;
+            if !violations.is_empty() {
+                run.failed_checks += 1;
+            }
+
+            let file_path = resource
+                .original_file_path()
+                .or(resource.patch_path())
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default();
+            for violation in violations {
+                let finding = Finding::new(
+                    rule.id().to_string(),
+                    severity.clone(),
+                    violation.message,
+                    resource.name().to_string(),
+                    file_path.clone(),
+                );
+                run.findings.push(match violation.column {
+                    Some(column) => finding.with_column(column),
+                    None => finding,
+                });
+            }
+        }
+    }
+
+    run.unused_suppressions = suppressions
+        .into_iter()
+        .zip(applied.into_iter().zip(used))
+        .filter(|(_, (applied, used))| *applied && !used)
+        .map(|(suppression, _)| suppression)
+        .collect();
+    Ok(run)
+}
+
+fn in_scope(scope: &Option<BTreeSet<NodeId>>, id: &NodeId) -> bool {
+    scope.as_ref().is_none_or(|ids| ids.contains(id))
+}
+
+/// Nodes in any of the scopes, `None` when one of them covers everything
+fn scoped(selector: &NodeSelector, scopes: &[RuleScope]) -> Result<Option<BTreeSet<NodeId>>> {
+    let specs = scopes
+        .iter()
+        .map(RuleScope::spec)
+        .collect::<Result<Option<Vec<_>>>>()?;
+    specs
+        .map(|specs| selector.select(&SelectionSpec::Union(specs)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuleConfig;
+    use crate::rules::implementations::DescriptionRule;
+    use crate::rules::{OptionKind, RuleOption, Violation};
+    use crate::types::dbt::{DbtNode, Model, ResourceType, Seed};
+    use crate::types::severity::RuleSeverity;
+
+    /// Flags seeds whose name lacks a configurable prefix
+    struct SeedPrefixRule;
+
+    impl Rule for SeedPrefixRule {
+        fn id(&self) -> &str {
+            "SEED_PREFIX"
+        }
+
+        fn resource_types(&self) -> &[ResourceType] {
+            &[ResourceType::Seed]
+        }
+
+        fn options_schema(&self) -> Vec<RuleOption> {
+            vec![RuleOption::new(
+                "prefix",
+                OptionKind::String,
+                "seed_",
+                "Prefix seed names must start with",
+            )]
+        }
+
+        fn evaluate(
+            &self,
+            resource: &dyn DbtResource,
+            context: &RuleContext,
+        ) -> Result<Vec<Violation>> {
+            let prefix = context.options.string("prefix").unwrap_or_default();
+            Ok(if resource.name().starts_with(prefix) {
+                Vec::new()
+            } else {
+                vec![Violation::new(format!(
+                    "{} doesn't start with {}",
+                    resource.name(),
+                    prefix
+                ))]
+            })
+        }
+    }
+
+    fn project() -> DbtProject {
+        let mut project = DbtProject::new("shop".to_string());
+        project.add_node(DbtNode::Model(Model::new("orders".to_string())));
+        project.add_node(DbtNode::Seed(Seed::new("ref_countries".to_string())));
+        project
+    }
+
+    #[test]
+    fn test_rules_run_on_their_resource_types_with_options() {
+        let project = project();
+        let rules: [&dyn Rule; 2] = [&DescriptionRule, &SeedPrefixRule];
+        let config = Config::default().with_rule(
+            "SEED_PREFIX",
+            RuleConfig {
+                severity: Some(RuleSeverity::Error),
+                options: [("prefix".to_string(), "ref_".into())].into(),
+                ..RuleConfig::default()
+            },
+        );
+
+        let run = run_rules(
+            &project,
+            &rules,
+            &config,
+            &NamedSelectors::default(),
+            &|_| true,
+        )
+        .unwrap();
+        // DOC001 checks both resources, SEED_PREFIX only the seed
+        assert_eq!(run.checks, 3);
+        assert_eq!(run.failed_checks, 2);
+        assert!(run.findings.iter().all(
+            |finding| finding.rule_id == "DOC001" && finding.severity == RuleSeverity::Warning
+        ));
+
+        let config = Config::default().with_rule(
+            "SEED_PREFIX",
+            RuleConfig {
+                options: [("prefix".to_string(), 3.into())].into(),
+                ..RuleConfig::default()
+            },
+        );
+        let error = run_rules(
+            &project,
+            &rules,
+            &config,
+            &NamedSelectors::default(),
+            &|_| true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "rules.SEED_PREFIX.prefix must be a string, got 3"
+        );
+    }
+
+    #[test]
+    fn test_rule_scopes() {
+        let mut project = project();
+        let mut legacy = Model::new("legacy_orders".to_string());
+        legacy.tags = vec!["deprecated".to_string()];
+        project.add_node(DbtNode::Model(legacy));
+        let mut revenue = Model::new("revenue".to_string());
+        revenue.sql_file = Some("models/marts/revenue.sql".into());
+        project.add_node(DbtNode::Model(revenue));
+
+        let rules: [&dyn Rule; 1] = [&DescriptionRule];
+        let checked = |toml: &str| {
+            let config = Config::parse(toml, std::path::Path::new("dbtective.toml")).unwrap();
+            let run = run_rules(
+                &project,
+                &rules,
+                &config,
+                &NamedSelectors::default(),
+                &|_| true,
+            )
+            .unwrap();
+            let mut names: Vec<String> = run
+                .findings
+                .into_iter()
+                .map(|finding| finding.resource_name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            checked("[rules.DOC001.exclude]\ntags = [\"deprecated\"]\n"),
+            ["orders", "ref_countries", "revenue"]
+        );
+        assert_eq!(
+            checked("[rules.DOC001.include]\nresource_types = [\"model\"]\npaths = [\"models/marts\"]\n"),
+            ["revenue"]
+        );
+        assert_eq!(
+            checked(
+                "[rules.DOC001]\ninclude = [{ resource_types = [\"seed\"] }, { select = \"orders\" }]\n"
+            ),
+            ["orders", "ref_countries"]
+        );
+
+        let error = Config::parse(
+            "[rules.DOC001.include]\nresource_type = [\"model\"]\n",
+            std::path::Path::new("dbtective.toml"),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown field `resource_type`"));
+    }
+
+    #[test]
+    fn test_descriptions_of_exposures_and_macros() {
+        let source = crate::source::MemorySource::new()
+            .with_file("dbt_project.yml", "name: shop\n")
+            .with_file("models/orders.sql", "select 1")
+            .with_file(
+                "macros/money.sql",
+                "{% macro cents_to_dollars(column) %} {{ column }} / 100 {% endmacro %}\n\
+                 {%- macro dollars_to_cents(column) -%} {{ column }} * 100 {%- endmacro %}",
+            )
+            .with_file(
+                "models/schema.yml",
+                r#"
+models:
+  - name: orders
+    description: One row per order placed in the shop
+macros:
+  - name: cents_to_dollars
+    description: Converts an amount in cents to dollars
+exposures:
+  - name: revenue_dashboard
+    type: dashboard
+    owner:
+      name: Finance
+    depends_on:
+      - ref('orders')
+"#,
+            );
+        let project = crate::parser::DbtProjectParser::new(&source)
+            .parse_project()
+            .unwrap()
+            .project;
+
+        let rules: [&dyn Rule; 1] = [&DescriptionRule];
+        let run = run_rules(
+            &project,
+            &rules,
+            &Config::default(),
+            &NamedSelectors::default(),
+            &|_| true,
+        )
+        .unwrap();
+        let mut messages: Vec<String> = run
+            .findings
+            .into_iter()
+            .map(|finding| finding.message)
+            .collect();
+        messages.sort();
+        assert_eq!(run.checks, 4);
+        assert_eq!(
+            messages,
+            [
+                "exposure revenue_dashboard is missing a description",
+                "macro dollars_to_cents is missing a description",
+            ]
+        );
+    }
+}
@@ -1,42 +1,162 @@
-use crate::rules::registry::RuleInfo;
-use crate::types::*;
+use crate::error::Result;
+use crate::rules::registry::RuleId;
+use crate::rules::{OptionKind, Rule, RuleContext, RuleOption, Violation};
+use crate::types::dbt::DbtResource;
+use serde_json::json;
 
+/// Descriptions that say nothing, compared ignoring case and trailing punctuation
+const PLACEHOLDERS: &[&str] = &[
+    "todo",
+    "tbd",
+    "tba",
+    "fixme",
+    "description",
+    "placeholder",
+    "n/a",
+    "none",
+];
+
+/// DOC001: resources must have a meaningful description
+#[derive(Debug, Default)]
 pub struct DescriptionRule;
+
 impl DescriptionRule {
     pub fn new() -> Self {
         Self
     }
+}
+
+impl Rule for DescriptionRule {
+    fn id(&self) -> &str {
+        RuleId::DOC001.as_str()
+    }
 
-    pub fn check(&self, resource: &DbtResource) -> RuleResult {
-        match &resource.description {
-            Some(desc) if !desc.trim().is_empty() => RuleResult {
-                rule_id: RuleId::DOC001,
-                passed: true,
-                message: None,
-                resource_name: resource.name.clone(),
-            },
-            _ => {
-                rule_id = RuleId::DOC001;
-                RuleResult {
-                    rule_id: RuleId::DOC001,
-                    passed: false,
-                    message: Some(
-                        format!("{}:Resource {} is missing a description"),
-                        rule_id.as_str(),
-                        resource.name.clone(),
-                    ),
-                }
+    fn options_schema(&self) -> Vec<RuleOption> {
+        vec![
+            RuleOption::new(
+                "min_description_length",
+                OptionKind::Integer,
+                10,
+                "Minimum length of a description, in characters",
+            ),
+            RuleOption::new(
+                "allow_empty_for_tags",
+                OptionKind::StringList,
+                json!([]),
+                "Resources with any of these tags may have no description",
+            ),
+        ]
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        context: &RuleContext,
+    ) -> Result<Vec<Violation>> {
+        let label = format!("{} {}", resource.resource_type().as_str(), resource.name());
+        let description = resource.description().as_deref().unwrap_or_default().trim();
+
+        if description.is_empty() {
+            let allowed_tags = context.options.string_list("allow_empty_for_tags");
+            if resource
+                .tags()
+                .iter()
+                .any(|tag| allowed_tags.contains(&tag.as_str()))
+            {
+                return Ok(Vec::new());
             }
+            return Ok(vec![Violation::new(format!(
+                "{} is missing a description",
+                label
+            ))]);
+        }
+
+        if is_placeholder(description, resource.name()) {
+            return Ok(vec![Violation::new(format!(
+                "{} has a placeholder description: '{}'",
+                label, description
+            ))]);
         }
+
+        let min_length = context
+            .options
+            .integer("min_description_length")
+            .unwrap_or(0);
+        let length = description.chars().count() as i64;
+        if length < min_length {
+            return Ok(vec![Violation::new(format!(
+                "{} has a description of {} characters, expected at least {}",
+                label, length, min_length
+            ))]);
+        }
+
+        Ok(Vec::new())
     }
 }
 
-impl Rule for DescriptionRule {
-    fn id(&self) -> RuleId {
-        RuleId::DOC001
-    }
+/// A known placeholder, one starting with `TODO`/`TBD`/`FIXME`, only punctuation,
+/// or the resource name repeated (`order_items` vs "Order items.")
+fn is_placeholder(description: &str, name: &str) -> bool {
+    let words = |text: &str| -> String {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric() && c != '/')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let description = words(description);
+    let first_word = description.split(' ').next().unwrap_or_default();
+
+    PLACEHOLDERS.contains(&description.as_str())
+        || description.is_empty()
+        || ["todo", "tbd", "fixme"].contains(&first_word)
+        || description == words(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::implementations::check_model;
+    use crate::types::dbt::{DbtProject, Model};
+
+    #[test]
+    fn test_descriptions() {
+        let rule = DescriptionRule::new();
+        let project = DbtProject::new("shop".to_string());
+        let check =
+            |model: Model, configured| check_model(&rule, &project, &model, configured).unwrap();
+        let described = |description: &str| {
+            Model::new("order_items".to_string()).with_description(description.to_string())
+        };
+
+        assert!(check(described("One row per item of an order"), json!({})).is_empty());
+        for placeholder in [
+            "TODO",
+            "tbd.",
+            "TODO: describe",
+            "Description",
+            "Order items",
+        ] {
+            assert_eq!(
+                check(described(placeholder), json!({})),
+                [format!(
+                    "model order_items has a placeholder description: '{}'",
+                    placeholder
+                )]
+            );
+        }
+        assert_eq!(
+            check(described("Items"), json!({})),
+            ["model order_items has a description of 5 characters, expected at least 10"]
+        );
+        assert!(check(described("Items"), json!({"min_description_length": 3})).is_empty());
 
-    fn evaluate(&self, resource: &DbtResource) -> RuleResult {
-        self.check(resource)
+        let mut staging = Model::new("stg_items".to_string());
+        assert_eq!(
+            check(staging.clone(), json!({})),
+            ["model stg_items is missing a description"]
+        );
+        staging.tags.push("staging".to_string());
+        assert!(check(staging, json!({"allow_empty_for_tags": ["staging"]})).is_empty());
     }
 }
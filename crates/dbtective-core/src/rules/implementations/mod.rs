@@ -0,0 +1,35 @@
+mod doc001;
+mod test001;
+mod test002;
+mod test003;
+
+pub use doc001::DescriptionRule;
+pub use test001::TestsRule;
+pub use test002::ModelTestsRule;
+pub use test003::ColumnTestsRule;
+
+/// Messages of a rule's findings on a model, with its options configured as
+/// in the `[rules.<ID>]` table of the config
+#[cfg(test)]
+fn check_model(
+    rule: &dyn crate::rules::Rule,
+    project: &crate::types::dbt::DbtProject,
+    model: &crate::types::dbt::Model,
+    configured: serde_json::Value,
+) -> crate::error::Result<Vec<String>> {
+    let options = crate::rules::RuleOptions::resolve(
+        rule.id(),
+        &rule.options_schema(),
+        &serde_json::from_value(configured).unwrap(),
+    )
+    .unwrap();
+    let context = crate::rules::RuleContext {
+        project,
+        options: &options,
+    };
+    let violations = rule.evaluate(model, &context)?;
+    Ok(violations
+        .into_iter()
+        .map(|violation| violation.message)
+        .collect())
+}
@@ -0,0 +1,264 @@
+use crate::error::Result;
+use crate::rules::registry::RuleId;
+use crate::rules::{OptionKind, Rule, RuleContext, RuleOption, Violation};
+use crate::selector::glob_match;
+use crate::types::dbt::{Column, DbtResource};
+use serde_json::json;
+
+/// TEST001: models must have tests
+#[derive(Debug, Default)]
+pub struct TestsRule;
+
+impl TestsRule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for TestsRule {
+    fn id(&self) -> &str {
+        RuleId::TEST001.as_str()
+    }
+
+    fn options_schema(&self) -> Vec<RuleOption> {
+        vec![
+            RuleOption::new(
+                "require_model_tests",
+                OptionKind::Boolean,
+                false,
+                "Require at least one model-level test",
+            ),
+            RuleOption::new(
+                "require_column_tests",
+                OptionKind::StringList,
+                json!(["primary_key", "foreign_key"]),
+                "Columns that must each have a test: `primary_key`, `foreign_key`, or names and globs like `*_id`",
+            ),
+            RuleOption::new(
+                "min_test_count",
+                OptionKind::Integer,
+                1,
+                "Minimum number of tests per model",
+            ),
+            RuleOption::new(
+                "required_test_types",
+                OptionKind::StringList,
+                json!(["not_null"]),
+                "Tests every model must have at model or column level",
+            ),
+            RuleOption::new(
+                "count_singular_tests",
+                OptionKind::Boolean,
+                false,
+                "Count singular tests in `tests/` that select from the model",
+            ),
+        ]
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        context: &RuleContext,
+    ) -> Result<Vec<Violation>> {
+        let options = context.options;
+        let label = format!("{} {}", resource.resource_type().as_str(), resource.name());
+        let singular_tests = if options.bool("count_singular_tests") {
+            context
+                .project
+                .singular_tests_of(resource.unique_id())
+                .count()
+        } else {
+            0
+        };
+        let model_tests = resource.tests();
+        let column_tests: Vec<_> = resource
+            .columns()
+            .iter()
+            .flat_map(|column| &column.tests)
+            .collect();
+        let mut messages = Vec::new();
+
+        let count = model_tests.len() + column_tests.len() + singular_tests;
+        let min_count = options.integer("min_test_count").unwrap_or(0);
+        if (count as i64) < min_count {
+            messages.push(Violation::new(format!(
+                "{} has {} {}, expected at least {}",
+                label,
+                count,
+                if count == 1 { "test" } else { "tests" },
+                min_count
+            )));
+        }
+
+        if options.bool("require_model_tests") && model_tests.is_empty() && singular_tests == 0 {
+            messages.push(Violation::new(format!(
+                "{} has no model-level tests",
+                label
+            )));
+        }
+
+        let required_columns = options.string_list("require_column_tests");
+        let untested: Vec<&str> = resource
+            .columns()
+            .iter()
+            .filter(|column| column.tests.is_empty())
+            .filter(|column| {
+                required_columns
+                    .iter()
+                    .any(|entry| is_required_column(resource, column, entry))
+            })
+            .map(|column| column.name.as_str())
+            .collect();
+        if !untested.is_empty() {
+            messages.push(Violation::new(format!(
+                "{} has untested columns: {}",
+                label,
+                untested.join(", ")
+            )));
+        }
+
+        let missing: Vec<&str> = options
+            .string_list("required_test_types")
+            .into_iter()
+            .filter(|test_type| {
+                !model_tests
+                    .iter()
+                    .chain(column_tests.iter().copied())
+                    .any(|test| test.matches(test_type))
+            })
+            .collect();
+        if !missing.is_empty() {
+            messages.push(Violation::new(format!(
+                "{} is missing required tests: {}",
+                label,
+                missing.join(", ")
+            )));
+        }
+
+        Ok(messages)
+    }
+}
+
+/// Whether a column is a `require_column_tests` entry: a `primary_key` or
+/// `foreign_key` by its constraints or `relationships` tests, or a name glob
+fn is_required_column(resource: &dyn DbtResource, column: &Column, entry: &str) -> bool {
+    match entry {
+        "primary_key" => has_constraint(resource, column, "primary_key"),
+        "foreign_key" => {
+            has_constraint(resource, column, "foreign_key")
+                || column
+                    .tests
+                    .iter()
+                    .any(|test| test.matches("relationships"))
+                || resource.tests().iter().any(|test| {
+                    test.matches("relationships")
+                        && test
+                            .arguments
+                            .get("column_name")
+                            .and_then(|name| name.as_str())
+                            == Some(column.name.as_str())
+                })
+        }
+        pattern => glob_match(pattern, &column.name),
+    }
+}
+
+/// A constraint on the column itself, or a model-level one listing it
+fn has_constraint(resource: &dyn DbtResource, column: &Column, constraint_type: &str) -> bool {
+    column
+        .constraints
+        .iter()
+        .any(|constraint| constraint.constraint_type == constraint_type)
+        || resource.constraints().iter().any(|constraint| {
+            constraint.constraint_type == constraint_type
+                && constraint.columns.contains(&column.name)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::implementations::check_model;
+    use crate::types::dbt::{Constraint, DataTest, DbtProject, Model, SingularTest};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_model_tests() {
+        let rule = TestsRule::new();
+        let mut project = DbtProject::new("shop".to_string());
+        project.add_singular_test(SingularTest {
+            name: "assert_positive_totals".to_string(),
+            file_path: PathBuf::from("tests/assert_positive_totals.sql"),
+            depends_on: vec!["model.shop.orders".to_string()],
+        });
+        let check =
+            |model: &Model, configured| check_model(&rule, &project, model, configured).unwrap();
+
+        let mut orders = Model::new("orders".to_string());
+        orders.unique_id = "model.shop.orders".to_string();
+        assert_eq!(
+            check(&orders, json!({})),
+            [
+                "model orders has 0 tests, expected at least 1",
+                "model orders is missing required tests: not_null"
+            ]
+        );
+        assert_eq!(
+            check(
+                &orders,
+                json!({"count_singular_tests": true, "required_test_types": []})
+            ),
+            Vec::<String>::new()
+        );
+
+        let mut id = Column::new("order_id".to_string());
+        id.tests = vec![DataTest::new("not_null"), DataTest::new("unique")];
+        orders.columns = vec![id, Column::new("customer_id".to_string())];
+        assert!(check(&orders, json!({})).is_empty());
+        assert_eq!(
+            check(
+                &orders,
+                json!({
+                    "require_model_tests": true,
+                    "require_column_tests": ["*_id"],
+                    "required_test_types": ["unique", "dbt_utils.not_empty_string"],
+                })
+            ),
+            [
+                "model orders has no model-level tests",
+                "model orders has untested columns: customer_id",
+                "model orders is missing required tests: dbt_utils.not_empty_string"
+            ]
+        );
+
+        // By default, primary and foreign key columns need tests
+        let mut customers = Model::new("customers".to_string());
+        customers.columns = vec![
+            Column {
+                tests: vec![DataTest::new("not_null")],
+                ..Column::new("name".to_string())
+            },
+            Column::new("customer_id".to_string()),
+            Column::new("region_id".to_string()),
+            Column::new("account_id".to_string()),
+            Column::new("email".to_string()),
+        ];
+        customers.constraints = vec![
+            Constraint {
+                columns: vec!["customer_id".to_string()],
+                ..Constraint::new("primary_key")
+            },
+            Constraint {
+                columns: vec!["region_id".to_string()],
+                ..Constraint::new("foreign_key")
+            },
+        ];
+        customers.tests =
+            vec![DataTest::new("relationships").with_argument("column_name", "account_id")];
+        assert_eq!(
+            check(&customers, json!({})),
+            ["model customers has untested columns: customer_id, region_id, account_id"]
+        );
+        assert!(check(&customers, json!({"require_column_tests": []})).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::Result;
+use crate::rules::registry::RuleId;
+use crate::rules::{OptionKind, Rule, RuleContext, RuleOption, Violation};
+use crate::types::dbt::DbtResource;
+use serde_json::json;
+
+/// TEST002: models must have model-level tests
+#[derive(Debug, Default)]
+pub struct ModelTestsRule;
+
+impl ModelTestsRule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for ModelTestsRule {
+    fn id(&self) -> &str {
+        RuleId::TEST002.as_str()
+    }
+
+    fn options_schema(&self) -> Vec<RuleOption> {
+        vec![
+            RuleOption::new(
+                "min_model_tests",
+                OptionKind::Integer,
+                1,
+                "Minimum number of model-level tests",
+            ),
+            RuleOption::new(
+                "required_model_test_types",
+                OptionKind::StringList,
+                json!([]),
+                "Tests every model must have at model level, e.g. `dbt_utils.row_count`",
+            ),
+            RuleOption::new(
+                "count_singular_tests",
+                OptionKind::Boolean,
+                false,
+                "Count singular tests in `tests/` that select from the model",
+            ),
+        ]
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        context: &RuleContext,
+    ) -> Result<Vec<Violation>> {
+        let options = context.options;
+        let label = format!("{} {}", resource.resource_type().as_str(), resource.name());
+        let model_tests = resource.tests();
+        let singular_tests = if options.bool("count_singular_tests") {
+            context
+                .project
+                .singular_tests_of(resource.unique_id())
+                .count()
+        } else {
+            0
+        };
+        let mut messages = Vec::new();
+
+        let count = model_tests.len() + singular_tests;
+        let min_count = options.integer("min_model_tests").unwrap_or(0);
+        if (count as i64) < min_count {
+            let has_column_tests = resource.columns().iter().any(|c| !c.tests.is_empty());
+            messages.push(Violation::new(format!(
+                "{} has {} model-level {}, expected at least {}{}",
+                label,
+                count,
+                if count == 1 { "test" } else { "tests" },
+                min_count,
+                if has_column_tests {
+                    " (column tests don't count)"
+                } else {
+                    ""
+                }
+            )));
+        }
+
+        let missing: Vec<&str> = options
+            .string_list("required_model_test_types")
+            .into_iter()
+            .filter(|test_type| !model_tests.iter().any(|test| test.matches(test_type)))
+            .collect();
+        if !missing.is_empty() {
+            messages.push(Violation::new(format!(
+                "{} is missing model-level tests: {}",
+                label,
+                missing.join(", ")
+            )));
+        }
+
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::implementations::check_model;
+    use crate::types::dbt::{Column, DataTest, DbtProject, Model};
+
+    #[test]
+    fn test_model_level_tests() {
+        let rule = ModelTestsRule::new();
+        let project = DbtProject::new("shop".to_string());
+        let check =
+            |model: &Model, configured| check_model(&rule, &project, model, configured).unwrap();
+
+        let mut orders = Model::new("orders".to_string());
+        let mut id = Column::new("id".to_string());
+        id.tests = vec![DataTest::new("dbt_utils.row_count")];
+        orders.columns = vec![id];
+        // Any row count test, from dbt_utils or dbt_expectations
+        let row_count_guard = json!({"required_model_test_types": ["*row_count*"]});
+        assert_eq!(
+            check(&orders, row_count_guard.clone()),
+            [
+                "model orders has 0 model-level tests, expected at least 1 (column tests don't count)",
+                "model orders is missing model-level tests: *row_count*"
+            ]
+        );
+
+        orders.tests = vec![
+            DataTest::new("dbt_expectations.expect_table_row_count_to_be_between")
+                .with_argument("min_value", 1),
+        ];
+        assert!(check(&orders, row_count_guard).is_empty());
+        assert!(check(
+            &orders,
+            json!({"required_model_test_types": ["dbt_expectations.*"]})
+        )
+        .is_empty());
+        assert_eq!(
+            check(
+                &orders,
+                json!({"required_model_test_types": ["dbt_utils.*"]})
+            ),
+            ["model orders is missing model-level tests: dbt_utils.*"]
+        );
+    }
+}
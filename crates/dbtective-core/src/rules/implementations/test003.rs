@@ -0,0 +1,316 @@
+use crate::error::{DbtectiveError, Result};
+use crate::rules::registry::RuleId;
+use crate::rules::{OptionKind, Rule, RuleContext, RuleOption, Violation};
+use crate::selector::glob_match;
+use crate::types::dbt::{Column, DataTest, DbtResource};
+use regex::Regex;
+use serde_json::json;
+
+/// TEST003: columns matching configured patterns must have specific tests
+#[derive(Debug, Default)]
+pub struct ColumnTestsRule;
+
+impl ColumnTestsRule {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A column name glob like `*_id`, or a regex written as `/^(pk|id)_/`
+enum ColumnPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl ColumnPattern {
+    fn parse(rule_id: &str, pattern: &str) -> Result<Self> {
+        match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) => Regex::new(regex).map(Self::Regex).map_err(|e| {
+                DbtectiveError::config(format!(
+                    "rules.{}: invalid column pattern {}: {}",
+                    rule_id, pattern, e
+                ))
+            }),
+            None => Ok(Self::Glob(pattern.to_string())),
+        }
+    }
+
+    fn matches(&self, column: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob_match(glob, column),
+            Self::Regex(regex) => regex.is_match(column),
+        }
+    }
+}
+
+impl Rule for ColumnTestsRule {
+    fn id(&self) -> &str {
+        RuleId::TEST003.as_str()
+    }
+
+    fn options_schema(&self) -> Vec<RuleOption> {
+        vec![
+            RuleOption::new(
+                "required_column_patterns",
+                OptionKind::StringList,
+                json!(["*_id", "id"]),
+                "Columns that need tests, as globs or `/regex/`",
+            ),
+            RuleOption::new(
+                "required_tests_per_column",
+                OptionKind::StringList,
+                json!(["not_null"]),
+                "Tests every column matching `required_column_patterns` needs",
+            ),
+            RuleOption::new(
+                "pattern_tests",
+                OptionKind::StringListMap,
+                json!({}),
+                "Tests needed by columns matching each pattern, instead of `required_tests_per_column`",
+            ),
+        ]
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        context: &RuleContext,
+    ) -> Result<Vec<Violation>> {
+        let options = context.options;
+        let label = format!("{} {}", resource.resource_type().as_str(), resource.name());
+        let patterns = options
+            .string_list("required_column_patterns")
+            .into_iter()
+            .map(|pattern| ColumnPattern::parse(self.id(), pattern))
+            .collect::<Result<Vec<_>>>()?;
+        let default_tests = options.string_list("required_tests_per_column");
+        let pattern_tests = options
+            .string_list_map("pattern_tests")
+            .into_iter()
+            .map(|(pattern, tests)| Ok((ColumnPattern::parse(self.id(), pattern)?, tests)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut messages = Vec::new();
+        for column in resource.columns() {
+            // Tests of every pattern entry matching the column, or the
+            // defaults when only `required_column_patterns` matches
+            let mut required: Vec<&str> = Vec::new();
+            for (_, tests) in pattern_tests
+                .iter()
+                .filter(|(pattern, _)| pattern.matches(&column.name))
+            {
+                for test in tests {
+                    if !required.contains(test) {
+                        required.push(test);
+                    }
+                }
+            }
+            if required.is_empty() && patterns.iter().any(|p| p.matches(&column.name)) {
+                required = default_tests.clone();
+            }
+            if required.is_empty() {
+                continue;
+            }
+
+            let tests = effective_tests(resource, column);
+            let missing: Vec<&str> = required
+                .into_iter()
+                .filter(|required| !tests.iter().any(|test| test.matches(required)))
+                .collect();
+            if !missing.is_empty() {
+                messages.push(
+                    Violation::new(format!(
+                        "{} column {} is missing tests: {}",
+                        label,
+                        column.name,
+                        missing.join(", ")
+                    ))
+                    .with_column(column.name.clone()),
+                );
+            }
+        }
+
+        Ok(messages)
+    }
+}
+
+/// The column's own tests, model-level tests given its `column_name`, and
+/// `unique` / `not_null` when a constraint or a
+/// `unique_combination_of_columns` test on just that column guarantees them
+fn effective_tests(resource: &dyn DbtResource, column: &Column) -> Vec<DataTest> {
+    let mut tests = column.tests.clone();
+    let model_tests = resource.tests();
+
+    tests.extend(
+        model_tests
+            .iter()
+            .filter(|test| {
+                test.arguments
+                    .get("column_name")
+                    .and_then(|name| name.as_str())
+                    == Some(column.name.as_str())
+            })
+            .cloned(),
+    );
+
+    // A combination of several columns is unique together, not each on its own
+    let unique_combination = model_tests.iter().any(|test| {
+        test.matches("unique_combination_of_columns")
+            && test
+                .arguments
+                .get("combination_of_columns")
+                .and_then(|columns| columns.as_sequence())
+                .is_some_and(|columns| {
+                    matches!(columns.as_slice(), [name] if name.as_str() == Some(column.name.as_str()))
+                })
+    });
+    if unique_combination {
+        tests.push(DataTest::new("unique"));
+    }
+
+    // Like a combination test, a constraint on several columns only makes them
+    // unique together, though each column of a primary key is still not null
+    let model_constraints = resource.constraints().iter().filter_map(|constraint| {
+        let constraint_type = constraint.constraint_type.as_str();
+        match constraint.columns.as_slice() {
+            [name] if *name == column.name => Some(constraint_type),
+            columns if columns.contains(&column.name) => match constraint_type {
+                "primary_key" | "not_null" => Some("not_null"),
+                _ => None,
+            },
+            _ => None,
+        }
+    });
+    let constraint_types = column
+        .constraints
+        .iter()
+        .map(|constraint| constraint.constraint_type.as_str())
+        .chain(model_constraints);
+    for constraint_type in constraint_types {
+        let implied: &[&str] = match constraint_type {
+            "primary_key" => &["unique", "not_null"],
+            "unique" => &["unique"],
+            "not_null" => &["not_null"],
+            _ => &[],
+        };
+        tests.extend(implied.iter().map(|name| DataTest::new(*name)));
+    }
+
+    tests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::implementations::check_model;
+    use crate::types::dbt::{Constraint, DbtProject, Model};
+
+    #[test]
+    fn test_column_patterns() {
+        let rule = ColumnTestsRule::new();
+        let project = DbtProject::new("shop".to_string());
+        let check = |model: &Model, configured| check_model(&rule, &project, model, configured);
+
+        let column = |name: &str, tests: &[&str]| Column {
+            tests: tests.iter().map(|test| DataTest::new(*test)).collect(),
+            ..Column::new(name.to_string())
+        };
+        let mut order_lines = Model::new("order_lines".to_string());
+        order_lines.columns = vec![
+            column("id", &["not_null"]),
+            column("order_id", &["not_null"]),
+            column("line", &[]),
+            column("amount", &[]),
+        ];
+
+        assert_eq!(
+            check(&order_lines, json!({})).unwrap(),
+            Vec::<String>::new()
+        );
+
+        let per_pattern = json!({
+            "pattern_tests": {
+                "*_id": ["not_null", "relationships"],
+                "id": ["unique", "not_null"],
+                "/^(line|position)$/": ["not_null"],
+            }
+        });
+        assert_eq!(
+            check(&order_lines, per_pattern.clone()).unwrap(),
+            [
+                "model order_lines column id is missing tests: unique",
+                "model order_lines column order_id is missing tests: relationships",
+                "model order_lines column line is missing tests: not_null",
+            ]
+        );
+
+        // Model-level equivalents: a constraint, and a model-level test
+        // naming the column. A composite key doesn't make `id` unique.
+        let unique_combination = |columns: &str| {
+            DataTest::new("dbt_utils.unique_combination_of_columns").with_argument(
+                "combination_of_columns",
+                serde_yaml::from_str::<serde_yaml::Value>(columns).unwrap(),
+            )
+        };
+        order_lines.tests = vec![
+            unique_combination("[id, line]"),
+            DataTest::new("relationships").with_argument("column_name", "order_id"),
+        ];
+        order_lines.constraints = vec![Constraint {
+            constraint_type: "not_null".to_string(),
+            columns: vec!["line".to_string()],
+        }];
+        assert_eq!(
+            check(&order_lines, per_pattern.clone()).unwrap(),
+            ["model order_lines column id is missing tests: unique"]
+        );
+        order_lines.tests.push(unique_combination("[id]"));
+        assert!(check(&order_lines, per_pattern).unwrap().is_empty());
+
+        // Composite constraints: only a primary key's not_null carries over
+        let unique_and_not_null = json!({"required_tests_per_column": ["unique", "not_null"]});
+        let mut shipments = Model::new("shipments".to_string());
+        shipments.columns = vec![column("order_id", &[]), column("carrier_id", &[])];
+        shipments.constraints = vec![
+            Constraint {
+                constraint_type: "unique".to_string(),
+                columns: vec!["order_id".to_string(), "carrier_id".to_string()],
+            },
+            Constraint {
+                constraint_type: "primary_key".to_string(),
+                columns: vec!["order_id".to_string(), "carrier_id".to_string()],
+            },
+        ];
+        assert_eq!(
+            check(&shipments, unique_and_not_null.clone()).unwrap(),
+            [
+                "model shipments column order_id is missing tests: unique",
+                "model shipments column carrier_id is missing tests: unique",
+            ]
+        );
+        shipments.constraints.push(Constraint {
+            constraint_type: "unique".to_string(),
+            columns: vec!["carrier_id".to_string()],
+        });
+        assert_eq!(
+            check(&shipments, unique_and_not_null).unwrap(),
+            ["model shipments column order_id is missing tests: unique"]
+        );
+
+        order_lines.columns[1].constraints = vec![Constraint::new("primary_key")];
+        assert!(check(
+            &order_lines,
+            json!({"required_tests_per_column": ["unique"]})
+        )
+        .unwrap()
+        .is_empty());
+
+        let error = check(&order_lines, json!({"required_column_patterns": ["/(/"]})).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("rules.TEST003: invalid column pattern /(/"));
+    }
+}
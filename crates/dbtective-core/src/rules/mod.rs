@@ -1 +1,106 @@
+pub mod custom;
+pub mod engine;
+pub mod implementations;
+pub mod options;
 pub mod registry;
+#[cfg(feature = "scripting")]
+pub mod script;
+
+pub use options::{OptionKind, RuleOption, RuleOptions};
+
+use crate::error::Result;
+use crate::types::dbt::{DbtProject, DbtResource, ResourceType};
+use crate::types::severity::RuleSeverity;
+use implementations::{ColumnTestsRule, DescriptionRule, ModelTestsRule, TestsRule};
+use registry::{RuleInfo, RuleRegistry};
+
+/// Directory (relative to the project root) holding script rules
+pub const RULES_DIR: &str = ".dbtective/rules";
+
+/// What a rule can see besides the resource it checks
+pub struct RuleContext<'a> {
+    pub project: &'a DbtProject,
+    /// Configured option values, over the defaults of the rule's schema
+    pub options: &'a RuleOptions,
+}
+
+/// A problem a rule found with a resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub message: String,
+    /// The column the problem is about, if any
+    pub column: Option<String>,
+}
+
+impl Violation {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            column: None,
+        }
+    }
+
+    pub fn with_column(mut self, column: String) -> Self {
+        self.column = Some(column);
+        self
+    }
+}
+
+impl From<String> for Violation {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// A check run against each applicable resource of a project. Implement it to
+/// add custom rules through [`AnalyzerBuilder::with_rule`](crate::AnalyzerBuilder::with_rule).
+pub trait Rule: Send + Sync {
+    /// Unique id used in findings and configuration, e.g. `DOC001`
+    fn id(&self) -> &str;
+
+    /// Name, category and documentation of built-in rules
+    fn info(&self) -> Option<&'static RuleInfo> {
+        RuleRegistry::get_rule_by_str(self.id())
+    }
+
+    /// Severity of findings unless the config overrides it
+    fn default_severity(&self) -> RuleSeverity {
+        self.info()
+            .map_or(RuleSeverity::Warning, |info| info.default_severity.clone())
+    }
+
+    /// Resource types the rule checks, all of them unless the registry says otherwise
+    fn resource_types(&self) -> &[ResourceType] {
+        self.info()
+            .map_or(ResourceType::all(), |info| info.resource_types)
+    }
+
+    /// Only check nodes matching this selector expression, as given to `--select`
+    fn selector(&self) -> Option<&str> {
+        None
+    }
+
+    /// Called once per run before any resource is checked, e.g. to index the project
+    fn prepare(&self, _project: &DbtProject) -> Result<()> {
+        Ok(())
+    }
+
+    /// Options the rule accepts, with their types and defaults
+    fn options_schema(&self) -> Vec<RuleOption> {
+        Vec::new()
+    }
+
+    /// Check one resource, returning a violation for every finding (none when it passes)
+    fn evaluate(&self, resource: &dyn DbtResource, context: &RuleContext)
+        -> Result<Vec<Violation>>;
+}
+
+/// Rules run when no explicit rule set is given
+pub fn builtin_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(DescriptionRule::new()),
+        Box::new(TestsRule::new()),
+        Box::new(ModelTestsRule::new()),
+        Box::new(ColumnTestsRule::new()),
+    ]
+}
@@ -0,0 +1,204 @@
+use crate::error::{DbtectiveError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Type of a rule option value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionKind {
+    Boolean,
+    Integer,
+    String,
+    StringList,
+    /// A table of string lists, e.g. tests keyed by column pattern
+    StringListMap,
+}
+
+impl OptionKind {
+    pub fn accepts(&self, value: &Value) -> bool {
+        match self {
+            Self::Boolean => value.is_boolean(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::String => value.is_string(),
+            Self::StringList => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            Self::StringListMap => value
+                .as_object()
+                .is_some_and(|map| map.values().all(|v| Self::StringList.accepts(v))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Boolean => "a boolean",
+            Self::Integer => "an integer",
+            Self::String => "a string",
+            Self::StringList => "a list of strings",
+            Self::StringListMap => "a table of lists of strings",
+        }
+    }
+}
+
+/// An option a rule accepts, part of its schema
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleOption {
+    pub name: &'static str,
+    pub kind: OptionKind,
+    pub default: Value,
+    pub description: &'static str,
+}
+
+impl RuleOption {
+    pub fn new(
+        name: &'static str,
+        kind: OptionKind,
+        default: impl Into<Value>,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            kind,
+            default: default.into(),
+            description,
+        }
+    }
+}
+
+/// The option values a rule runs with
+#[derive(Debug, Clone, Default)]
+pub struct RuleOptions {
+    values: HashMap<String, Value>,
+}
+
+impl RuleOptions {
+    /// Schema defaults overridden by configured values. Configured options
+    /// must be in the schema and have the declared type.
+    pub fn resolve(
+        rule_id: &str,
+        schema: &[RuleOption],
+        configured: &HashMap<String, Value>,
+    ) -> Result<Self> {
+        let mut values: HashMap<String, Value> = schema
+            .iter()
+            .map(|option| (option.name.to_string(), option.default.clone()))
+            .collect();
+
+        for (name, value) in configured {
+            let option = schema
+                .iter()
+                .find(|option| option.name == name)
+                .ok_or_else(|| {
+                    let known: Vec<&str> = schema.iter().map(|option| option.name).collect();
+                    DbtectiveError::config(format!(
+                        "rules.{}: unknown option '{}'{}",
+                        rule_id,
+                        name,
+                        if known.is_empty() {
+                            " (the rule has no options)".to_string()
+                        } else {
+                            format!(", expected one of: {}", known.join(", "))
+                        }
+                    ))
+                })?;
+            if !option.kind.accepts(value) {
+                return Err(DbtectiveError::config(format!(
+                    "rules.{}.{} must be {}, got {}",
+                    rule_id,
+                    name,
+                    option.kind.as_str(),
+                    value
+                )));
+            }
+            values.insert(name.clone(), value.clone());
+        }
+
+        Ok(Self { values })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn bool(&self, name: &str) -> bool {
+        self.get(name).and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        self.get(name).and_then(Value::as_i64)
+    }
+
+    pub fn string(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(Value::as_str)
+    }
+
+    pub fn string_list(&self, name: &str) -> Vec<&str> {
+        self.get(name)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Entries of a [`OptionKind::StringListMap`] option, sorted by key
+    pub fn string_list_map(&self, name: &str) -> Vec<(&str, Vec<&str>)> {
+        self.get(name)
+            .and_then(Value::as_object)
+            .map(|map| {
+                map.iter()
+                    .map(|(key, items)| {
+                        let items = items.as_array().map(Vec::as_slice).unwrap_or_default();
+                        (
+                            key.as_str(),
+                            items.iter().filter_map(Value::as_str).collect(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_options() {
+        let schema = vec![
+            RuleOption::new("min_length", OptionKind::Integer, 10, "Minimum length"),
+            RuleOption::new(
+                "skip_tags",
+                OptionKind::StringList,
+                json!([]),
+                "Tags to skip",
+            ),
+        ];
+        let configured =
+            |value: Value| -> HashMap<String, Value> { serde_json::from_value(value).unwrap() };
+
+        let options = RuleOptions::resolve("DOC001", &schema, &HashMap::new()).unwrap();
+        assert_eq!(options.integer("min_length"), Some(10));
+        assert!(options.string_list("skip_tags").is_empty());
+
+        let options = RuleOptions::resolve(
+            "DOC001",
+            &schema,
+            &configured(json!({"skip_tags": ["wip"]})),
+        )
+        .unwrap();
+        assert_eq!(options.string_list("skip_tags"), ["wip"]);
+
+        let error =
+            RuleOptions::resolve("DOC001", &schema, &configured(json!({"min": 3}))).unwrap_err();
+        assert!(error.to_string().contains("unknown option 'min'"));
+        let error =
+            RuleOptions::resolve("DOC001", &schema, &configured(json!({"min_length": "ten"})))
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "rules.DOC001.min_length must be an integer, got \"ten\""
+        );
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, Clone)]
+use crate::types::dbt::ResourceType;
+use crate::types::severity::RuleSeverity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RuleCategory {
     Documentation,
     Testing,
@@ -17,7 +21,7 @@ impl RuleCategory {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RuleId {
     DOC001,
     TEST001,
@@ -35,6 +39,7 @@ impl RuleId {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "DOC001" => Some(RuleId::DOC001),
@@ -46,12 +51,17 @@ impl RuleId {
     }
 }
 
+#[derive(Serialize)]
 pub struct RuleInfo {
     pub id: RuleId,
     pub category: RuleCategory,
     pub name: &'static str,
     pub short_description: &'static str,
     pub long_description: &'static str,
+    /// Severity of findings unless configured otherwise
+    pub default_severity: RuleSeverity,
+    /// Resource types the rule checks
+    pub resource_types: &'static [ResourceType],
 }
 
 pub struct RuleRegistry;
@@ -92,6 +102,15 @@ impl RuleRegistry {
         name: "description",
         short_description: "Resources must have description",
         long_description: include_str!("definitions/DOC/DOC001.md"),
+        default_severity: RuleSeverity::Warning,
+        resource_types: &[
+            ResourceType::Model,
+            ResourceType::Source,
+            ResourceType::Seed,
+            ResourceType::Snapshot,
+            ResourceType::Exposure,
+            ResourceType::Macro,
+        ],
     };
 
     pub const TESTS: RuleInfo = RuleInfo {
@@ -100,6 +119,8 @@ impl RuleRegistry {
         name: "tests",
         short_description: "Models must have tests (either column or model-level",
         long_description: include_str!("definitions/TEST/TEST001.md"),
+        default_severity: RuleSeverity::Warning,
+        resource_types: &[ResourceType::Model],
     };
 
     pub const MODEL_TESTS: RuleInfo = RuleInfo {
@@ -108,6 +129,8 @@ impl RuleRegistry {
         name: "model-tests",
         short_description: "Models must have model-level tests",
         long_description: include_str!("definitions/TEST/TEST002.md"),
+        default_severity: RuleSeverity::Warning,
+        resource_types: &[ResourceType::Model],
     };
 
     pub const COLUMN_TESTS: RuleInfo = RuleInfo {
@@ -116,5 +139,7 @@ impl RuleRegistry {
         name: "column-tests",
         short_description: "Specific columns must have tests",
         long_description: include_str!("definitions/TEST/TEST003.md"),
+        default_severity: RuleSeverity::Warning,
+        resource_types: &[ResourceType::Model],
     };
 }
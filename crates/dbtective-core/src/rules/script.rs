@@ -0,0 +1,439 @@
+use super::{Rule, RuleContext, Violation, RULES_DIR};
+use crate::error::{DbtectiveError, Result};
+use crate::source::FileSource;
+use crate::types::dbt::{DbtProject, DbtResource, NodeId, ResourceType};
+use crate::types::severity::RuleSeverity;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope, AST};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a script may take to check one resource
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A rule written as a Rhai script. The script defines
+/// `fn check(resource, project)` returning a message, a list of messages or
+/// nothing:
+///
+/// ```rhai
+/// const SEVERITY = "error";
+/// const RESOURCE_TYPES = ["model"];
+///
+/// fn check(resource, project) {
+///     if resource.meta.owner == () && project.children(resource.unique_id).len() > 0 {
+///         return `${resource.name} has downstream models but no owner`;
+///     }
+/// }
+/// ```
+///
+/// `resource` is the resource's [JSON form](DbtResource::to_json) as a map.
+/// `project` is read-only, with `package_name`, `get(unique_id)`,
+/// `resources()`, `parents(unique_id)` and `children(unique_id)`.
+///
+/// The rule id is the file name in upper case (`owner_check.rhai` is
+/// `OWNER_CHECK`) unless the script sets `const ID`. Scripts can't import
+/// modules, and are stopped once they exceed their time limit.
+pub struct ScriptRule {
+    id: String,
+    path: PathBuf,
+    severity: Option<RuleSeverity>,
+    resource_types: Vec<ResourceType>,
+    engine: Engine,
+    ast: AST,
+    timeout: Duration,
+    /// Nanoseconds since `started` after which the running script is stopped
+    deadline: Arc<AtomicU64>,
+    started: Instant,
+    project: Mutex<Option<ScriptProject>>,
+}
+
+/// Read-only view of the project given to scripts
+#[derive(Clone)]
+struct ScriptProject(Arc<ProjectIndex>);
+
+struct ProjectIndex {
+    package_name: String,
+    /// Resource maps in node order
+    resources: Vec<(String, Dynamic)>,
+    index: HashMap<String, usize>,
+    parents: Vec<Vec<usize>>,
+    children: Vec<Vec<usize>>,
+}
+
+impl ScriptProject {
+    fn new(project: &DbtProject) -> Result<Self> {
+        let mut resources = Vec::new();
+        let mut index = HashMap::new();
+        for resource in project.resources() {
+            index.insert(resource.unique_id().to_string(), resources.len());
+            resources.push((resource.unique_id().to_string(), to_dynamic(resource)?));
+        }
+        let position = |ids: &[NodeId]| {
+            ids.iter()
+                .filter_map(|id| index.get(project.unique_id(*id)).copied())
+                .collect()
+        };
+        let parents = project
+            .ids()
+            .map(|id| position(project.parents(id)))
+            .collect();
+        let children = project
+            .ids()
+            .map(|id| position(project.children(id)))
+            .collect();
+
+        Ok(Self(Arc::new(ProjectIndex {
+            package_name: project.package_name().to_string(),
+            resources,
+            index,
+            parents,
+            children,
+        })))
+    }
+
+    fn get(&mut self, unique_id: &str) -> Dynamic {
+        self.0
+            .index
+            .get(unique_id)
+            .map_or(Dynamic::UNIT, |&i| self.0.resources[i].1.clone())
+    }
+
+    fn resources(&mut self) -> Array {
+        self.0.resources.iter().map(|(_, r)| r.clone()).collect()
+    }
+
+    fn lineage(&self, unique_id: &str, edges: &[Vec<usize>]) -> Array {
+        self.0.index.get(unique_id).map_or_else(Array::new, |&i| {
+            edges[i]
+                .iter()
+                .map(|&j| self.0.resources[j].1.clone())
+                .collect()
+        })
+    }
+}
+
+impl ScriptRule {
+    /// Compile a script, read from `path` relative to the project root
+    pub fn new(path: &Path, script: &str) -> Result<Self> {
+        let deadline = Arc::new(AtomicU64::new(u64::MAX));
+        let started = Instant::now();
+        let engine = sandboxed_engine(path, started, deadline.clone());
+        let ast = engine.compile(script).map_err(|e| DbtectiveError::Config {
+            path: Some(path.to_path_buf()),
+            line: e.position().line(),
+            message: e.err_type().to_string(),
+        })?;
+        let invalid = |message: String| DbtectiveError::Config {
+            path: Some(path.to_path_buf()),
+            line: None,
+            message,
+        };
+
+        let mut id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_uppercase().replace('-', "_"))
+            .unwrap_or_default();
+        let mut severity = None;
+        let mut resource_types = Vec::new();
+        for (name, _, value) in ast.iter_literal_variables(true, false) {
+            let value = rhai::serde::from_dynamic::<serde_json::Value>(&value)
+                .map_err(|e| invalid(format!("{}: {}", name, e)))?;
+            match name {
+                "ID" => {
+                    id = serde_json::from_value(value)
+                        .map_err(|_| invalid("ID must be a string".to_string()))?
+                }
+                "SEVERITY" => {
+                    severity = Some(serde_json::from_value(value).map_err(|_| {
+                        invalid("SEVERITY must be \"error\", \"warning\" or \"info\"".to_string())
+                    })?)
+                }
+                "RESOURCE_TYPES" => {
+                    resource_types = serde_json::from_value(value)
+                        .map_err(|e| invalid(format!("RESOURCE_TYPES: {}", e)))?
+                }
+                _ => {}
+            }
+        }
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == "check" && f.params.len() == 2)
+        {
+            return Err(invalid(
+                "scripts must define fn check(resource, project)".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id,
+            path: path.to_path_buf(),
+            severity,
+            resource_types,
+            engine,
+            ast,
+            timeout: DEFAULT_TIMEOUT,
+            deadline,
+            started,
+            project: Mutex::new(None),
+        })
+    }
+
+    /// Stop checking a resource after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn error(&self, error: &EvalAltResult) -> DbtectiveError {
+        let message = match error {
+            EvalAltResult::ErrorTerminated(..) => format!(
+                "{}: took longer than {:?} to check a resource",
+                self.path.display(),
+                self.timeout
+            ),
+            error => format!("{}: {}", self.path.display(), error),
+        };
+        DbtectiveError::Rule {
+            rule_id: self.id.clone(),
+            message,
+        }
+    }
+}
+
+/// Every `*.rhai` script in `.dbtective/rules`, in file name order
+pub fn load_scripts(source: &dyn FileSource) -> Result<Vec<ScriptRule>> {
+    source
+        .files_in(Path::new(RULES_DIR))?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+        .map(|path| ScriptRule::new(&path, &source.read_to_string(&path)?))
+        .collect()
+}
+
+/// An engine without module imports or `eval`, with bounded sizes, that
+/// stops scripts running past the shared deadline
+fn sandboxed_engine(path: &Path, started: Instant, deadline: Arc<AtomicU64>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .set_max_call_levels(64)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(1 << 20)
+        .set_max_array_size(100_000)
+        .set_max_map_size(100_000)
+        .on_progress(move |operations| {
+            let expired = operations % 1024 == 0
+                && started.elapsed().as_nanos() as u64 > deadline.load(Ordering::Relaxed);
+            expired.then_some(Dynamic::UNIT)
+        });
+
+    // Script output goes to stderr, to keep reports on stdout intact
+    let script = path.display().to_string();
+    engine.on_print(move |text| eprintln!("{}: {}", script, text));
+    let script = path.display().to_string();
+    engine.on_debug(move |text, _, position| eprintln!("{} {}: {}", script, position, text));
+
+    engine
+        .register_type_with_name::<ScriptProject>("Project")
+        .register_get("package_name", |project: &mut ScriptProject| {
+            project.0.package_name.clone()
+        })
+        .register_fn("get", ScriptProject::get)
+        .register_fn("resources", ScriptProject::resources)
+        .register_fn("parents", |project: &mut ScriptProject, unique_id: &str| {
+            project.lineage(unique_id, &project.0.parents)
+        })
+        .register_fn(
+            "children",
+            |project: &mut ScriptProject, unique_id: &str| {
+                project.lineage(unique_id, &project.0.children)
+            },
+        );
+    engine
+}
+
+fn to_dynamic(resource: &dyn DbtResource) -> Result<Dynamic> {
+    rhai::serde::to_dynamic(resource.to_json()).map_err(|e| DbtectiveError::Rule {
+        rule_id: resource.unique_id().to_string(),
+        message: e.to_string(),
+    })
+}
+
+impl Rule for ScriptRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn default_severity(&self) -> RuleSeverity {
+        self.severity.clone().unwrap_or(RuleSeverity::Warning)
+    }
+
+    fn resource_types(&self) -> &[ResourceType] {
+        if self.resource_types.is_empty() {
+            ResourceType::all()
+        } else {
+            &self.resource_types
+        }
+    }
+
+    fn prepare(&self, project: &DbtProject) -> Result<()> {
+        let index = ScriptProject::new(project)?;
+        *self.project.lock().unwrap_or_else(|e| e.into_inner()) = Some(index);
+        Ok(())
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        context: &RuleContext,
+    ) -> Result<Vec<Violation>> {
+        let project = {
+            let mut prepared = self.project.lock().unwrap_or_else(|e| e.into_inner());
+            match prepared.as_ref() {
+                Some(project) => project.clone(),
+                None => prepared
+                    .insert(ScriptProject::new(context.project)?)
+                    .clone(),
+            }
+        };
+
+        let deadline = self.started.elapsed() + self.timeout;
+        self.deadline
+            .store(deadline.as_nanos() as u64, Ordering::Relaxed);
+        let result = self.engine.call_fn::<Dynamic>(
+            &mut Scope::new(),
+            &self.ast,
+            "check",
+            (to_dynamic(resource)?, project),
+        );
+        self.deadline.store(u64::MAX, Ordering::Relaxed);
+        let result = result.map_err(|e| self.error(&e))?;
+
+        let invalid = || DbtectiveError::Rule {
+            rule_id: self.id.clone(),
+            message: format!(
+                "{}: check must return a string, an array of strings or nothing",
+                self.path.display()
+            ),
+        };
+        if result.is_unit() {
+            Ok(Vec::new())
+        } else if result.is_string() {
+            Ok(vec![Violation::new(result.to_string())])
+        } else if let Some(messages) = result.try_cast::<Array>() {
+            messages
+                .into_iter()
+                .map(|message| {
+                    message
+                        .into_string()
+                        .map(Violation::new)
+                        .map_err(|_| invalid())
+                })
+                .collect()
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleOptions;
+    use crate::source::MemorySource;
+    use crate::types::dbt::{DbtNode, Model, Seed};
+
+    #[test]
+    fn test_script_rules() {
+        let source = MemorySource::new()
+            .with_file(
+                ".dbtective/rules/orphan-seeds.rhai",
+                r#"
+const SEVERITY = "error";
+const RESOURCE_TYPES = ["seed"];
+
+fn check(resource, project) {
+    let users = project.children(resource.unique_id);
+    if users.is_empty() {
+        return `${resource.name} isn't used by any model of ${project.package_name}`;
+    }
+    users.filter(|model| model.description == ()).map(|model| `${model.name} uses ${resource.name} undocumented`)
+}
+"#,
+            )
+            .with_file(".dbtective/rules/notes.txt", "not a script");
+        let rules = load_scripts(&source).unwrap();
+        assert_eq!(rules.len(), 1);
+        let rule = &rules[0];
+        assert_eq!(rule.id(), "ORPHAN_SEEDS");
+        assert_eq!(rule.default_severity(), RuleSeverity::Error);
+        assert_eq!(rule.resource_types(), [ResourceType::Seed]);
+
+        let mut project = DbtProject::new("shop".to_string());
+        let countries = project.add_node(DbtNode::Seed(Seed::new("countries".to_string())));
+        let currencies = project.add_node(DbtNode::Seed(Seed::new("currencies".to_string())));
+        let orders = project.add_node(DbtNode::Model(Model::new("orders".to_string())));
+        project.add_dependency(orders, countries);
+        let options = RuleOptions::default();
+        let context = RuleContext {
+            project: &project,
+            options: &options,
+        };
+        rule.prepare(&project).unwrap();
+        let check = |id| {
+            let resource = project.node(id).as_resource();
+            rule.evaluate(resource, &context).map(|violations| {
+                violations
+                    .into_iter()
+                    .map(|v| v.message)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            check(countries).unwrap(),
+            ["orders uses countries undocumented"]
+        );
+        assert_eq!(
+            check(currencies).unwrap(),
+            ["currencies isn't used by any model of shop"]
+        );
+
+        let script = |body: &str| ScriptRule::new(Path::new("loop.rhai"), body);
+        let endless = script("fn check(resource, project) { loop {} }")
+            .unwrap()
+            .with_timeout(Duration::from_millis(50));
+        assert_eq!(
+            endless
+                .evaluate(project.node(orders).as_resource(), &context)
+                .unwrap_err()
+                .to_string(),
+            "Rule LOOP failed: loop.rhai: took longer than 50ms to check a resource"
+        );
+
+        let failing = script("fn check(resource, project) {\n    resource.name.nope()\n}").unwrap();
+        let error = failing
+            .evaluate(project.node(orders).as_resource(), &context)
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Rule LOOP failed: loop.rhai: Function not found: nope"));
+        assert!(error.contains("line 2"));
+
+        let error = script("import \"x\" as x;\nfn check(resource, project) { x::check() }")
+            .unwrap()
+            .evaluate(project.node(orders).as_resource(), &context)
+            .unwrap_err();
+        assert!(error.to_string().contains("Module not found"));
+
+        assert_eq!(
+            script("fn check(resource) {\n  1 +\n}")
+                .err()
+                .unwrap()
+                .to_string(),
+            "loop.rhai:3: Unexpected '}'"
+        );
+    }
+}
@@ -0,0 +1,22 @@
+/// Percentage of (rule, resource) checks that passed, rounded to one decimal.
+/// A project with nothing to check scores 100.
+pub fn overall_score(checks: usize, failed_checks: usize) -> f64 {
+    if checks == 0 {
+        return 100.0;
+    }
+
+    let passed = checks.saturating_sub(failed_checks) as f64;
+    (passed / checks as f64 * 1000.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overall_score() {
+        assert_eq!(overall_score(0, 0), 100.0);
+        assert_eq!(overall_score(4, 1), 75.0);
+        assert_eq!(overall_score(3, 1), 66.7);
+    }
+}
@@ -0,0 +1,671 @@
+//! dbt's node selection syntax, e.g. `--select tag:finance +orders,config.materialized:incremental`.
+//!
+//! Space-separated selectors are unioned, comma-separated ones intersected.
+//! Each selector is `[@][n+]method:value[+n]`, where `+` adds ancestors (in
+//! front) or descendants (behind), optionally limited to `n` generations,
+//! and `@` adds descendants together with all of their ancestors.
+
+pub mod named;
+
+pub use named::{NamedSelector, NamedSelectors, SELECTORS_FILE};
+
+use crate::error::{DbtectiveError, Result};
+use crate::types::dbt::{DbtNode, DbtProject, NodeId, ResourceType, Source};
+use serde_yaml::Value;
+use std::collections::{BTreeSet, VecDeque};
+use std::path::Path;
+
+/// How a selector matches resources, the part before the `:`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorMethod {
+    Tag,
+    Path,
+    Package,
+    ResourceType,
+    /// `config.<key>`, e.g. `config.materialized`. Nested keys are dotted.
+    Config(String),
+    Fqn,
+    Source,
+    /// A named selector from `selectors.yml`
+    Selector,
+}
+
+impl SelectorMethod {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(method: &str) -> Option<Self> {
+        match method {
+            "tag" => Some(Self::Tag),
+            "path" => Some(Self::Path),
+            "package" => Some(Self::Package),
+            "resource_type" => Some(Self::ResourceType),
+            "fqn" => Some(Self::Fqn),
+            "source" => Some(Self::Source),
+            "selector" => Some(Self::Selector),
+            _ => method
+                .strip_prefix("config.")
+                .filter(|key| !key.is_empty())
+                .map(|key| Self::Config(key.to_string())),
+        }
+    }
+}
+
+/// A single method and value with its graph operators, like `2+config.materialized:incremental+`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionCriteria {
+    pub method: SelectorMethod,
+    pub value: String,
+    /// Include ancestors, up to `parents_depth` generations when set
+    pub parents: bool,
+    pub parents_depth: Option<usize>,
+    /// Include descendants, up to `children_depth` generations when set
+    pub children: bool,
+    pub children_depth: Option<usize>,
+    /// Include descendants and all of their ancestors (`@`)
+    pub childrens_parents: bool,
+}
+
+impl SelectionCriteria {
+    pub fn new(method: SelectorMethod, value: impl Into<String>) -> Self {
+        Self {
+            method,
+            value: value.into(),
+            parents: false,
+            parents_depth: None,
+            children: false,
+            children_depth: None,
+            childrens_parents: false,
+        }
+    }
+
+    /// Parse one selector in CLI syntax. Without a method, values that look
+    /// like a file path select by path and anything else by fqn, as in dbt.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            DbtectiveError::config(format!("Invalid selector '{}': {}", raw, reason))
+        };
+
+        let (childrens_parents, rest) = match raw.strip_prefix('@') {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let (parents, parents_depth, rest) = match rest[digits..].strip_prefix('+') {
+            Some(after) => (true, rest[..digits].parse().ok(), after),
+            None => (false, None, rest),
+        };
+
+        let without_depth = rest.trim_end_matches(|c: char| c.is_ascii_digit());
+        let (children, children_depth, rest) = match without_depth.strip_suffix('+') {
+            Some(before) => (true, rest[without_depth.len()..].parse().ok(), before),
+            None => (false, None, rest),
+        };
+
+        if childrens_parents && parents {
+            return Err(invalid("'@' and a leading '+' can't be combined"));
+        }
+
+        let (method, value) = match rest.split_once(':') {
+            Some((method, value)) => {
+                let method = SelectorMethod::from_str(method)
+                    .ok_or_else(|| invalid(&format!("unknown method '{}'", method)))?;
+                (method, value)
+            }
+            None if looks_like_path(rest) => (SelectorMethod::Path, rest),
+            None => (SelectorMethod::Fqn, rest),
+        };
+        if value.is_empty() {
+            return Err(invalid("missing a value"));
+        }
+        if method == SelectorMethod::ResourceType && ResourceType::from_str(value).is_none() {
+            return Err(invalid(&format!("unknown resource type '{}'", value)));
+        }
+
+        Ok(Self {
+            method,
+            value: value.to_string(),
+            parents,
+            parents_depth,
+            children,
+            children_depth,
+            childrens_parents,
+        })
+    }
+}
+
+/// A set expression over selection criteria
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionSpec {
+    Criteria(SelectionCriteria),
+    Union(Vec<SelectionSpec>),
+    Intersection(Vec<SelectionSpec>),
+    /// Everything selected by the first spec and not by the second
+    Difference(Box<SelectionSpec>, Box<SelectionSpec>),
+}
+
+impl SelectionSpec {
+    /// Parse `--select` or `--exclude` arguments. Arguments and their
+    /// space-separated parts are unioned, comma-separated parts intersected.
+    pub fn parse_cli<S: AsRef<str>>(args: &[S]) -> Result<Self> {
+        let union = args
+            .iter()
+            .flat_map(|arg| arg.as_ref().split_whitespace())
+            .map(|part| {
+                let mut criteria = part
+                    .split(',')
+                    .map(|raw| SelectionCriteria::parse(raw).map(Self::Criteria))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(match criteria.len() {
+                    1 => criteria.remove(0),
+                    _ => Self::Intersection(criteria),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::Union(union))
+    }
+}
+
+/// Which resources to analyze: `--select`, `--exclude` and `--selector`
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Selectors in CLI syntax. Empty selects everything, unless
+    /// `selectors.yml` has a default selector.
+    pub select: Vec<String>,
+    pub exclude: Vec<String>,
+    /// A named selector from `selectors.yml`, used instead of `select`
+    pub selector: Option<String>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.select.is_empty() && self.exclude.is_empty() && self.selector.is_none()
+    }
+
+    /// The nodes of the project this selection picks
+    pub fn select(
+        &self,
+        project: &DbtProject,
+        selectors: &NamedSelectors,
+    ) -> Result<BTreeSet<NodeId>> {
+        let selector = NodeSelector::new(project, selectors);
+        let named = |name: &str| {
+            SelectionSpec::Criteria(SelectionCriteria::new(SelectorMethod::Selector, name))
+        };
+
+        let mut selected = match (&self.selector, selectors.default_selector()) {
+            (Some(name), _) => selector.select(&named(name))?,
+            (None, _) if !self.select.is_empty() => {
+                selector.select(&SelectionSpec::parse_cli(&self.select)?)?
+            }
+            (None, Some(default)) => selector.select(&named(&default.name))?,
+            (None, None) => project.ids().collect(),
+        };
+
+        if !self.exclude.is_empty() {
+            let excluded = selector.select(&SelectionSpec::parse_cli(&self.exclude)?)?;
+            selected.retain(|id| !excluded.contains(id));
+        }
+
+        Ok(selected)
+    }
+}
+
+/// Evaluates selection specs against a parsed project
+pub struct NodeSelector<'a> {
+    project: &'a DbtProject,
+    selectors: &'a NamedSelectors,
+}
+
+impl<'a> NodeSelector<'a> {
+    pub fn new(project: &'a DbtProject, selectors: &'a NamedSelectors) -> Self {
+        Self { project, selectors }
+    }
+
+    pub fn select(&self, spec: &SelectionSpec) -> Result<BTreeSet<NodeId>> {
+        self.evaluate(spec, &mut Vec::new())
+    }
+
+    /// `stack` holds the named selectors being expanded, to catch cycles
+    fn evaluate(&self, spec: &SelectionSpec, stack: &mut Vec<String>) -> Result<BTreeSet<NodeId>> {
+        match spec {
+            SelectionSpec::Criteria(criteria) => self.evaluate_criteria(criteria, stack),
+            SelectionSpec::Union(specs) => {
+                let mut selected = BTreeSet::new();
+                for spec in specs {
+                    selected.extend(self.evaluate(spec, stack)?);
+                }
+                Ok(selected)
+            }
+            SelectionSpec::Intersection(specs) => {
+                let mut selected: Option<BTreeSet<NodeId>> = None;
+                for spec in specs {
+                    let matched = self.evaluate(spec, stack)?;
+                    selected = Some(match selected {
+                        Some(selected) => selected.intersection(&matched).copied().collect(),
+                        None => matched,
+                    });
+                }
+                Ok(selected.unwrap_or_default())
+            }
+            SelectionSpec::Difference(selected, excluded) => {
+                let excluded = self.evaluate(excluded, stack)?;
+                let mut selected = self.evaluate(selected, stack)?;
+                selected.retain(|id| !excluded.contains(id));
+                Ok(selected)
+            }
+        }
+    }
+
+    fn evaluate_criteria(
+        &self,
+        criteria: &SelectionCriteria,
+        stack: &mut Vec<String>,
+    ) -> Result<BTreeSet<NodeId>> {
+        let matched = match &criteria.method {
+            SelectorMethod::Selector => {
+                let name = &criteria.value;
+                if stack.contains(name) {
+                    return Err(DbtectiveError::config(format!(
+                        "Selector '{}' refers to itself",
+                        name
+                    )));
+                }
+                let selector = self.selectors.get(name).ok_or_else(|| {
+                    DbtectiveError::config(format!(
+                        "Unknown selector '{}', it isn't defined in {}",
+                        name, SELECTORS_FILE
+                    ))
+                })?;
+
+                stack.push(name.clone());
+                let selected = self.evaluate(&selector.definition, stack)?;
+                stack.pop();
+                selected
+            }
+            method => self
+                .project
+                .nodes()
+                .filter(|(_, node)| matches(method, &criteria.value, node))
+                .map(|(id, _)| id)
+                .collect(),
+        };
+
+        Ok(self.expand(matched, criteria))
+    }
+
+    /// Apply the graph operators of `criteria` to the nodes it matched
+    fn expand(&self, matched: BTreeSet<NodeId>, criteria: &SelectionCriteria) -> BTreeSet<NodeId> {
+        let mut selected = matched.clone();
+        if criteria.childrens_parents {
+            let mut descendants = self.walk(&matched, None, |id| self.project.children(id));
+            descendants.extend(&matched);
+            selected.extend(self.walk(&descendants, None, |id| self.project.parents(id)));
+            selected.extend(descendants);
+        }
+        if criteria.parents {
+            selected.extend(self.walk(&matched, criteria.parents_depth, |id| {
+                self.project.parents(id)
+            }));
+        }
+        if criteria.children {
+            selected.extend(self.walk(&matched, criteria.children_depth, |id| {
+                self.project.children(id)
+            }));
+        }
+        selected
+    }
+
+    /// Nodes reachable from `start` in at most `depth` steps (any number when unset)
+    fn walk(
+        &self,
+        start: &BTreeSet<NodeId>,
+        depth: Option<usize>,
+        next: impl Fn(NodeId) -> &'a [NodeId],
+    ) -> BTreeSet<NodeId> {
+        let mut reached = BTreeSet::new();
+        let mut queue: VecDeque<(NodeId, usize)> = start.iter().map(|&id| (id, 0)).collect();
+
+        while let Some((id, distance)) = queue.pop_front() {
+            if depth.is_some_and(|depth| distance >= depth) {
+                continue;
+            }
+            for &neighbour in next(id) {
+                if reached.insert(neighbour) {
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        reached
+    }
+}
+
+fn matches(method: &SelectorMethod, value: &str, node: &DbtNode) -> bool {
+    let resource = node.as_resource();
+    match method {
+        SelectorMethod::Tag => resource.tags().iter().any(|tag| glob_match(value, tag)),
+        SelectorMethod::Path => resource
+            .original_file_path()
+            .is_some_and(|path| path_matches(value, path)),
+        SelectorMethod::Package => glob_match(value, resource.package_name()),
+        SelectorMethod::ResourceType => {
+            ResourceType::from_str(value).is_some_and(|t| t == resource.resource_type())
+        }
+        SelectorMethod::Config(key) => resource
+            .lookup(&format!("config.{}", key))
+            .is_some_and(|config| config_matches(value, config)),
+        SelectorMethod::Fqn => {
+            let fqn = resource.fqn();
+            fqn_matches(value, fqn) || fqn_matches(value, fqn.get(1..).unwrap_or_default())
+        }
+        SelectorMethod::Source => match node {
+            DbtNode::Source(source) => source_matches(value, source),
+            _ => false,
+        },
+        SelectorMethod::Selector => false,
+    }
+}
+
+fn looks_like_path(value: &str) -> bool {
+    value.contains('/')
+        || value.contains('\\')
+        || [".sql", ".yml", ".yaml", ".csv"]
+            .iter()
+            .any(|extension| value.ends_with(extension))
+}
+
+/// A file, a directory containing it, or a glob over project-relative paths
+fn path_matches(value: &str, path: &Path) -> bool {
+    let value = value.trim_start_matches("./").trim_end_matches('/');
+    if value.contains(['*', '?']) {
+        return glob_match(value, &path.to_string_lossy());
+    }
+    path.starts_with(value)
+}
+
+/// dbt's fqn matching: a bare resource name, or a dotted prefix of the fqn
+fn fqn_matches(value: &str, fqn: &[String]) -> bool {
+    if fqn.last().is_some_and(|name| glob_match(value, name)) {
+        return true;
+    }
+
+    let parts: Vec<&str> = value.split('.').collect();
+    parts.len() <= fqn.len()
+        && parts
+            .iter()
+            .zip(fqn)
+            .all(|(part, segment)| glob_match(part, segment))
+}
+
+/// `source_name`, `source_name.table` or `package.source_name.table`
+fn source_matches(value: &str, source: &Source) -> bool {
+    let parts: Vec<&str> = value.split('.').collect();
+    let expected: &[&str] = match parts.len() {
+        1 => &[source.source_name.as_str()],
+        2 => &[source.source_name.as_str(), source.name.as_str()],
+        3 => &[
+            source.package_name.as_str(),
+            source.source_name.as_str(),
+            source.name.as_str(),
+        ],
+        _ => return false,
+    };
+    parts
+        .iter()
+        .zip(expected)
+        .all(|(part, actual)| glob_match(part, actual))
+}
+
+fn config_matches(value: &str, config: &Value) -> bool {
+    match config {
+        Value::String(config) => glob_match(value, config),
+        Value::Bool(config) => value.eq_ignore_ascii_case(&config.to_string()),
+        Value::Number(config) => value == config.to_string(),
+        Value::Sequence(items) => items.iter().any(|item| config_matches(value, item)),
+        Value::Tagged(tagged) => config_matches(value, &tagged.value),
+        Value::Null | Value::Mapping(_) => false,
+    }
+}
+
+/// Shell-style matching where `*` matches any run of characters and `?` a single one
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently absorbs up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, absorbed)) => {
+                    p = star + 1;
+                    t = absorbed + 1;
+                    backtrack = Some((star, absorbed + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DbtProjectParser;
+    use crate::source::MemorySource;
+
+    fn project() -> DbtProject {
+        let source = MemorySource::new()
+            .with_file("dbt_project.yml", "name: shop\n")
+            .with_file(
+                "models/staging/stg_orders.sql",
+                "select * from {{ source('raw', 'orders') }}",
+            )
+            .with_file(
+                "models/staging/stg_customers.sql",
+                "select * from {{ source('raw', 'customers') }}",
+            )
+            .with_file(
+                "models/marts/orders.sql",
+                "{{ config(materialized='incremental', tags=['finance']) }}
+                 select * from {{ ref('stg_orders') }} join {{ ref('stg_customers') }}",
+            )
+            .with_file(
+                "models/marts/revenue.sql",
+                "{{ config(tags=['finance', 'daily']) }} select * from {{ ref('orders') }}",
+            )
+            .with_file(
+                "models/staging/sources.yml",
+                "sources:\n  - name: raw\n    tables:\n      - name: orders\n      - name: customers\n",
+            )
+            .with_file("seeds/countries.csv", "code\nNL\n");
+        DbtProjectParser::new(&source)
+            .parse_project()
+            .unwrap()
+            .project
+    }
+
+    fn names(
+        project: &DbtProject,
+        selection: &Selection,
+        selectors: &NamedSelectors,
+    ) -> Vec<String> {
+        let mut names: Vec<String> = selection
+            .select(project, selectors)
+            .unwrap()
+            .into_iter()
+            .map(|id| project.unique_id(id).to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn select(project: &DbtProject, select: &[&str], exclude: &[&str]) -> Vec<String> {
+        let selection = Selection {
+            select: select.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            selector: None,
+        };
+        names(project, &selection, &NamedSelectors::default())
+    }
+
+    #[test]
+    fn test_parse_graph_operators() {
+        let criteria = SelectionCriteria::parse("2+config.materialized:incremental+").unwrap();
+        assert_eq!(
+            criteria.method,
+            SelectorMethod::Config("materialized".to_string())
+        );
+        assert_eq!(criteria.value, "incremental");
+        assert!(criteria.parents && criteria.children);
+        assert_eq!(criteria.parents_depth, Some(2));
+        assert_eq!(criteria.children_depth, None);
+
+        let criteria = SelectionCriteria::parse("@models/marts").unwrap();
+        assert_eq!(criteria.method, SelectorMethod::Path);
+        assert!(criteria.childrens_parents);
+
+        assert!(SelectionCriteria::parse("colour:red").is_err());
+        assert!(SelectionCriteria::parse("@+orders").is_err());
+        assert!(SelectionCriteria::parse("resource_type:widget").is_err());
+    }
+
+    #[test]
+    fn test_methods() {
+        let project = project();
+        assert_eq!(
+            select(&project, &["tag:finance"], &[]),
+            ["model.shop.orders", "model.shop.revenue"]
+        );
+        assert_eq!(
+            select(&project, &["path:models/staging"], &[]),
+            [
+                "model.shop.stg_customers",
+                "model.shop.stg_orders",
+                "source.shop.raw.customers",
+                "source.shop.raw.orders"
+            ]
+        );
+        assert_eq!(
+            select(&project, &["config.materialized:incremental"], &[]),
+            ["model.shop.orders"]
+        );
+        assert_eq!(
+            select(&project, &["resource_type:seed"], &[]),
+            ["seed.shop.countries"]
+        );
+        assert_eq!(
+            select(&project, &["source:raw.orders"], &[]),
+            ["source.shop.raw.orders"]
+        );
+        assert_eq!(
+            select(&project, &["fqn:shop.marts.*"], &[]),
+            ["model.shop.orders", "model.shop.revenue"]
+        );
+        assert_eq!(select(&project, &["stg_*"], &[]).len(), 2);
+        assert_eq!(select(&project, &["package:shop"], &[]).len(), 7);
+    }
+
+    #[test]
+    fn test_graph_operators_and_set_operations() {
+        let project = project();
+        assert_eq!(
+            select(&project, &["1+revenue"], &[]),
+            ["model.shop.orders", "model.shop.revenue"]
+        );
+        // A bare name matches the model and the source table alike
+        assert_eq!(select(&project, &["+orders"], &[]).len(), 5);
+        assert_eq!(
+            select(&project, &["stg_orders+"], &[]),
+            [
+                "model.shop.orders",
+                "model.shop.revenue",
+                "model.shop.stg_orders"
+            ]
+        );
+        // Descendants plus everything they depend on
+        assert_eq!(select(&project, &["@stg_orders"], &[]).len(), 6);
+
+        assert_eq!(
+            select(&project, &["tag:finance,tag:daily"], &[]),
+            ["model.shop.revenue"]
+        );
+        assert_eq!(
+            select(&project, &["tag:daily resource_type:seed"], &[]),
+            ["model.shop.revenue", "seed.shop.countries"]
+        );
+        assert_eq!(
+            select(&project, &["+revenue"], &["resource_type:source", "stg_*"]),
+            ["model.shop.orders", "model.shop.revenue"]
+        );
+        assert_eq!(select(&project, &[], &["resource_type:model"]).len(), 3);
+    }
+
+    #[test]
+    fn test_named_selectors() {
+        let project = project();
+        let selectors = NamedSelectors::parse(
+            r#"
+selectors:
+  - name: finance
+    definition:
+      union:
+        - tag: finance
+        - exclude:
+            - method: config.materialized
+              value: incremental
+  - name: finance_lineage
+    default: true
+    definition:
+      intersection:
+        - method: selector
+          value: finance
+          parents: true
+          parents_depth: 1
+        - "resource_type:model"
+  - name: loop
+    definition: "selector:loop"
+"#,
+        )
+        .unwrap();
+
+        // The default selector applies when nothing else is selected
+        assert_eq!(
+            names(&project, &Selection::default(), &selectors),
+            ["model.shop.orders", "model.shop.revenue"]
+        );
+
+        let named = |name: &str| Selection {
+            selector: Some(name.to_string()),
+            ..Selection::default()
+        };
+        assert_eq!(
+            names(&project, &named("finance"), &selectors),
+            ["model.shop.revenue"]
+        );
+        assert!(named("loop").select(&project, &selectors).is_err());
+        assert!(named("missing").select(&project, &selectors).is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("stg_*", "stg_orders"));
+        assert!(glob_match("*orders", "stg_orders"));
+        assert!(glob_match("s?g_*s", "stg_orders"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("stg_*", "orders"));
+        assert!(!glob_match("orders", "orders_v2"));
+    }
+}
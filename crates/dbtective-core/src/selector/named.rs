@@ -0,0 +1,192 @@
+use super::{SelectionCriteria, SelectionSpec, SelectorMethod};
+use crate::error::{DbtectiveError, Result};
+use crate::source::FileSource;
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::path::Path;
+
+pub const SELECTORS_FILE: &str = "selectors.yml";
+
+/// A selector defined in `selectors.yml`
+#[derive(Debug, Clone)]
+pub struct NamedSelector {
+    pub name: String,
+    pub description: Option<String>,
+    /// Used when nothing is selected explicitly
+    pub default: bool,
+    pub definition: SelectionSpec,
+}
+
+/// The named selectors of a project, in the order they are defined
+#[derive(Debug, Clone, Default)]
+pub struct NamedSelectors {
+    selectors: Vec<NamedSelector>,
+}
+
+#[derive(Deserialize)]
+struct SelectorsFile {
+    #[serde(default)]
+    selectors: Vec<SelectorYaml>,
+}
+
+#[derive(Deserialize)]
+struct SelectorYaml {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    default: bool,
+    definition: Value,
+}
+
+impl NamedSelectors {
+    /// Load `selectors.yml` from the project root. A missing file defines no selectors.
+    pub fn load(source: &dyn FileSource) -> Result<Self> {
+        let path = Path::new(SELECTORS_FILE);
+        if !source.exists(path) {
+            return Ok(Self::default());
+        }
+
+        Self::from_yaml(&source.read_to_string(path)?, path)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::from_yaml(content, Path::new(SELECTORS_FILE))
+    }
+
+    fn from_yaml(content: &str, path: &Path) -> Result<Self> {
+        let file: Option<SelectorsFile> =
+            serde_yaml::from_str(content).map_err(|e| DbtectiveError::config_yaml(path, e))?;
+
+        let mut selectors = Vec::new();
+        for selector in file.map(|file| file.selectors).unwrap_or_default() {
+            let definition =
+                definition(&selector.definition).map_err(|message| DbtectiveError::Config {
+                    path: Some(path.to_path_buf()),
+                    line: None,
+                    message: format!("selector '{}': {}", selector.name, message),
+                })?;
+            selectors.push(NamedSelector {
+                name: selector.name,
+                description: selector.description,
+                default: selector.default,
+                definition,
+            });
+        }
+
+        let defaults = selectors.iter().filter(|s| s.default).count();
+        if defaults > 1 {
+            return Err(DbtectiveError::Config {
+                path: Some(path.to_path_buf()),
+                line: None,
+                message: format!("{} selectors are marked default, only one may be", defaults),
+            });
+        }
+
+        Ok(Self { selectors })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NamedSelector> {
+        self.selectors.iter().find(|selector| selector.name == name)
+    }
+
+    /// The selector marked `default: true`, if any
+    pub fn default_selector(&self) -> Option<&NamedSelector> {
+        self.selectors.iter().find(|selector| selector.default)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NamedSelector> {
+        self.selectors.iter()
+    }
+}
+
+/// Convert a YAML selector definition: a CLI-style string, a `union` or
+/// `intersection` list (which may contain an `exclude` list), a `method`/`value`
+/// mapping with graph operator flags, or a `method: value` shorthand
+fn definition(value: &Value) -> std::result::Result<SelectionSpec, String> {
+    let mapping = match value {
+        Value::String(selector) => {
+            return SelectionSpec::parse_cli(&[selector]).map_err(|e| e.to_string())
+        }
+        Value::Mapping(mapping) => mapping,
+        _ => return Err("a definition must be a string or a mapping".to_string()),
+    };
+
+    if let Some(items) = mapping.get("union") {
+        return set_operation(items, SelectionSpec::Union);
+    }
+    if let Some(items) = mapping.get("intersection") {
+        return set_operation(items, SelectionSpec::Intersection);
+    }
+
+    let (method, value) = match mapping.get("method") {
+        Some(method) => (
+            scalar(method)?,
+            scalar(mapping.get("value").unwrap_or(&Value::Null))?,
+        ),
+        None if mapping.len() == 1 => {
+            let (method, value) = mapping
+                .iter()
+                .next()
+                .unwrap_or((&Value::Null, &Value::Null));
+            (scalar(method)?, scalar(value)?)
+        }
+        None => return Err("expected union, intersection or method".to_string()),
+    };
+
+    let method =
+        SelectorMethod::from_str(&method).ok_or_else(|| format!("unknown method '{}'", method))?;
+    if value.is_empty() {
+        return Err("missing a value".to_string());
+    }
+
+    let flag = |key: &str| mapping.get(key).and_then(Value::as_bool).unwrap_or(false);
+    let depth = |key: &str| mapping.get(key).and_then(Value::as_u64).map(|d| d as usize);
+    let criteria = SelectionCriteria {
+        parents: flag("parents") || depth("parents_depth").is_some(),
+        parents_depth: depth("parents_depth"),
+        children: flag("children") || depth("children_depth").is_some(),
+        children_depth: depth("children_depth"),
+        childrens_parents: flag("childrens_parents"),
+        ..SelectionCriteria::new(method, value)
+    };
+    Ok(SelectionSpec::Criteria(criteria))
+}
+
+fn set_operation(
+    items: &Value,
+    operation: fn(Vec<SelectionSpec>) -> SelectionSpec,
+) -> std::result::Result<SelectionSpec, String> {
+    let Value::Sequence(items) = items else {
+        return Err("union and intersection take a list".to_string());
+    };
+
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for item in items {
+        match item.get("exclude") {
+            Some(Value::Sequence(exclusions)) => {
+                for exclusion in exclusions {
+                    excluded.push(definition(exclusion)?);
+                }
+            }
+            Some(_) => return Err("exclude takes a list".to_string()),
+            None => included.push(definition(item)?),
+        }
+    }
+
+    let selected = operation(included);
+    Ok(if excluded.is_empty() {
+        selected
+    } else {
+        SelectionSpec::Difference(Box::new(selected), Box::new(SelectionSpec::Union(excluded)))
+    })
+}
+
+fn scalar(value: &Value) -> std::result::Result<String, String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Number(value) => Ok(value.to_string()),
+        _ => Err("methods and values must be strings".to_string()),
+    }
+}
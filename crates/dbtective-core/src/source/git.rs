@@ -0,0 +1,215 @@
+use super::{is_ignored_dir, FileSource};
+use crate::error::{DbtectiveError, Result};
+use crate::rules::RULES_DIR;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A project read from a commit in a local git repository, straight from the
+/// object store and without touching the working tree
+pub struct GitSource {
+    // git2 repositories can move between threads but not be shared
+    repository: Mutex<Repository>,
+    rev: String,
+    commit: Oid,
+    name: Option<String>,
+    files: BTreeMap<PathBuf, Oid>,
+}
+
+impl GitSource {
+    /// Read the project at `project_path` as of `rev`, anything `git rev-parse`
+    /// understands (`origin/main`, `HEAD~2`, a tag or a sha). The project may
+    /// live in a subdirectory of the repository.
+    pub fn open(project_path: &Path, rev: &str) -> Result<Self> {
+        let git_error = |e: git2::Error| DbtectiveError::git(rev, e.message());
+        let repository = Repository::discover(project_path).map_err(git_error)?;
+        let subdir = project_subdir(&repository, project_path)?;
+
+        let (commit, files) = {
+            let commit = repository
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(git_error)?;
+            let mut tree = commit.tree().map_err(git_error)?;
+            if subdir.components().next().is_some() {
+                tree = tree
+                    .get_path(&subdir)
+                    .and_then(|entry| entry.to_object(&repository))
+                    .and_then(|object| object.peel_to_tree())
+                    .map_err(|_| {
+                        DbtectiveError::git(
+                            rev,
+                            format!("{} doesn't exist in this revision", subdir.display()),
+                        )
+                    })?;
+            }
+
+            let mut files = BTreeMap::new();
+            tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                let name = entry.name().unwrap_or_default();
+                match entry.kind() {
+                    // Script rules live in a hidden directory
+                    Some(ObjectType::Tree)
+                        if is_ignored_dir(name)
+                            && !Path::new(RULES_DIR).starts_with(Path::new(dir).join(name)) =>
+                    {
+                        TreeWalkResult::Skip
+                    }
+                    Some(ObjectType::Blob) => {
+                        files.insert(Path::new(dir).join(name), entry.id());
+                        TreeWalkResult::Ok
+                    }
+                    _ => TreeWalkResult::Ok,
+                }
+            })
+            .map_err(git_error)?;
+
+            (commit.id(), files)
+        };
+
+        let name = subdir
+            .file_name()
+            .or_else(|| repository.workdir().and_then(Path::file_name))
+            .map(|name| name.to_string_lossy().to_string());
+
+        Ok(Self {
+            repository: Mutex::new(repository),
+            rev: rev.to_string(),
+            commit,
+            name,
+            files,
+        })
+    }
+
+    /// The commit `rev` resolved to
+    pub fn commit(&self) -> Oid {
+        self.commit
+    }
+}
+
+impl FileSource for GitSource {
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        let oid = self.files.get(path).ok_or_else(|| {
+            DbtectiveError::io(path, std::io::Error::from(std::io::ErrorKind::NotFound))
+        })?;
+
+        let repository = self.repository.lock().unwrap_or_else(|e| e.into_inner());
+        let blob = repository
+            .find_blob(*oid)
+            .map_err(|e| DbtectiveError::git(&self.rev, e.message()))?;
+        Ok(String::from_utf8_lossy(blob.content()).to_string())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn describe(&self) -> String {
+        let commit = self.commit.to_string();
+        format!("{} ({})", self.rev, &commit[..7])
+    }
+
+    fn root_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
+
+/// Where the project sits inside the repository's working tree
+fn project_subdir(repository: &Repository, project_path: &Path) -> Result<PathBuf> {
+    let Some(workdir) = repository.workdir() else {
+        // A bare repository has no working tree to be inside of
+        return Ok(PathBuf::new());
+    };
+
+    let canonical = |path: &Path| path.canonicalize().map_err(|e| DbtectiveError::io(path, e));
+    Ok(canonical(project_path)?
+        .strip_prefix(canonical(workdir)?)
+        .map(Path::to_path_buf)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DbtProjectParser;
+    use git2::Signature;
+
+    fn commit_all(repository: &Repository, message: &str) {
+        let mut index = repository.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("dbtective", "dbtective@example.com").unwrap();
+        let parents: Vec<_> = repository
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap();
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_read_project_from_revision() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = Repository::init(dir.path()).unwrap();
+        let project = dir.path().join("analytics");
+        write(&project, "dbt_project.yml", "name: analytics\n");
+        write(&project, "models/orders.sql", "select 1");
+        write(dir.path(), "README.md", "not part of the project");
+        commit_all(&repository, "first");
+
+        write(&project, "models/customers.sql", "select 1");
+        commit_all(&repository, "second");
+        std::fs::remove_file(project.join("models/orders.sql")).unwrap();
+
+        let source = GitSource::open(&project, "HEAD~1").unwrap();
+        assert_eq!(
+            source.files().unwrap(),
+            vec![
+                PathBuf::from("dbt_project.yml"),
+                PathBuf::from("models/orders.sql")
+            ]
+        );
+        assert_eq!(
+            source
+                .read_to_string(Path::new("models/orders.sql"))
+                .unwrap(),
+            "select 1"
+        );
+
+        let head = GitSource::open(&project, "HEAD").unwrap();
+        let parsed = DbtProjectParser::new(&head).parse_project().unwrap();
+        assert!(parsed.project.get("model.analytics.orders").is_some());
+        assert!(parsed.project.get("model.analytics.customers").is_some());
+        assert!(head.describe().starts_with("HEAD ("));
+
+        assert!(matches!(
+            GitSource::open(&project, "no-such-branch"),
+            Err(DbtectiveError::Git { .. })
+        ));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::error::{DbtectiveError, Result};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "git")]
+mod git;
+#[cfg(feature = "git")]
+pub use git::GitSource;
+
+/// Directories that never contain project sources (build output, installed packages, ...)
+pub const IGNORED_DIRS: &[&str] = &["target", "dbt_packages", "dbt_modules", "logs"];
+
+/// Where the files of a dbt project come from. Paths are always relative to the project root.
+pub trait FileSource: Send + Sync {
+    /// Every file of the project. Sources may skip ignored directories
+    /// (see [`is_ignored_dir`]) up front; the parser filters out the rest.
+    fn files(&self) -> Result<Vec<PathBuf>>;
+
+    fn read_to_string(&self, path: &Path) -> Result<String>;
+
+    /// Files directly in a directory, including hidden ones like
+    /// `.dbtective/rules` that [`files`](Self::files) may skip
+    fn files_in(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = self
+            .files()?
+            .into_iter()
+            .filter(|path| path.parent() == Some(dir))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Where the project lives, for reports and messages
+    fn describe(&self) -> String;
+
+    /// Name of the project root, the package name fallback when `dbt_project.yml` has none
+    fn root_name(&self) -> Option<String> {
+        None
+    }
+
+    /// Local directory of the project, if any. The parse cache is only used when there is one.
+    fn local_root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Hidden directories and [`IGNORED_DIRS`] are never searched for project files
+pub fn is_ignored_dir(name: &str) -> bool {
+    name.starts_with('.') || IGNORED_DIRS.contains(&name)
+}
+
+/// A project checked out on the local filesystem
+#[cfg(feature = "fs")]
+#[derive(Debug, Clone)]
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+#[cfg(feature = "fs")]
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl FileSource for DirectorySource {
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        if !self.root.is_dir() {
+            return Err(DbtectiveError::ProjectNotFound {
+                path: self.root.clone(),
+            });
+        }
+
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0
+                    || !(e.file_type().is_dir() && is_ignored_dir(&e.file_name().to_string_lossy()))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                e.path()
+                    .strip_prefix(&self.root)
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .collect();
+        files.sort();
+
+        Ok(files)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        let path = self.root.join(path);
+        std::fs::read_to_string(&path).map_err(|e| DbtectiveError::io(&path, e))
+    }
+
+    fn files_in(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(self.root.join(dir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(DbtectiveError::io(&self.root.join(dir), e)),
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+            .map(|entry| dir.join(entry.file_name()))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn describe(&self) -> String {
+        self.root.to_string_lossy().to_string()
+    }
+
+    fn root_name(&self) -> Option<String> {
+        let root = self.root.canonicalize().ok()?;
+        root.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+
+    fn local_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
+
+/// A project held in memory as a map of paths to contents, e.g. for tests or uploads
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    name: Option<String>,
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Project name used when `dbt_project.yml` doesn't set one
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_file(mut self, path: impl Into<PathBuf>, content: impl Into<String>) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    pub fn insert(&mut self, path: impl Into<PathBuf>, content: impl Into<String>) {
+        self.files.insert(normalize(&path.into()), content.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl<P: Into<PathBuf>, C: Into<String>> FromIterator<(P, C)> for MemorySource {
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(files: I) -> Self {
+        let mut source = Self::new();
+        for (path, content) in files {
+            source.insert(path, content);
+        }
+        source
+    }
+}
+
+impl FileSource for MemorySource {
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            DbtectiveError::io(path, std::io::Error::from(std::io::ErrorKind::NotFound))
+        })
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} (in memory)", name),
+            None => "in-memory project".to_string(),
+        }
+    }
+
+    fn root_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
+
+/// A project packed in a `.tar` or `.tar.gz` archive, read fully into memory.
+///
+/// When every file sits below the same top-level directory (as with
+/// `tar czf project.tar.gz jaffle_shop/`) that directory is the project root.
+#[derive(Debug, Clone)]
+pub struct TarballSource {
+    description: String,
+    files: MemorySource,
+}
+
+impl TarballSource {
+    #[cfg(feature = "fs")]
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(|e| DbtectiveError::io(path, e))?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = name
+            .trim_end_matches(".gz")
+            .trim_end_matches(".tgz")
+            .trim_end_matches(".tar");
+
+        let mut source = Self::from_reader(file, &path.to_string_lossy())?;
+        if source.files.name.is_none() && !name.is_empty() {
+            source.files.name = Some(name.to_string());
+        }
+        Ok(source)
+    }
+
+    /// Read an archive, gzip compressed or not. `description` names it in messages.
+    pub fn from_reader(mut reader: impl Read, description: &str) -> Result<Self> {
+        let archive_path = Path::new(description);
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| DbtectiveError::io(archive_path, e))?;
+
+        let is_gzip = bytes.starts_with(&[0x1f, 0x8b]);
+        let reader: Box<dyn Read> = if is_gzip {
+            Box::new(flate2::read::GzDecoder::new(bytes.as_slice()))
+        } else {
+            Box::new(bytes.as_slice())
+        };
+
+        let mut files = BTreeMap::new();
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive
+            .entries()
+            .map_err(|e| DbtectiveError::io(archive_path, e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| DbtectiveError::io(archive_path, e))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = entry
+                .path()
+                .map_err(|e| DbtectiveError::io(archive_path, e))?
+                .to_path_buf();
+            let mut content = Vec::new();
+            entry
+                .read_to_end(&mut content)
+                .map_err(|e| DbtectiveError::io(archive_path, e))?;
+            files.insert(
+                normalize(&path),
+                String::from_utf8_lossy(&content).to_string(),
+            );
+        }
+
+        let (name, files) = strip_common_root(files);
+        Ok(Self {
+            description: description.to_string(),
+            files: MemorySource { name, files },
+        })
+    }
+}
+
+impl FileSource for TarballSource {
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        self.files.files()
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files.read_to_string(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.exists(path)
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+
+    fn root_name(&self) -> Option<String> {
+        self.files.root_name()
+    }
+}
+
+/// Drop `./` and empty components so lookups match however the path was written
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Remove a top-level directory shared by every file, returning its name
+fn strip_common_root(
+    files: BTreeMap<PathBuf, String>,
+) -> (Option<String>, BTreeMap<PathBuf, String>) {
+    let first_components: Vec<_> = files
+        .keys()
+        .map(|path| {
+            let mut components = path.components();
+            let first = components.next();
+            (first, components.next().is_some())
+        })
+        .collect();
+
+    let root = match first_components.first() {
+        Some((Some(root), true)) => *root,
+        _ => return (None, files),
+    };
+    let shared = first_components
+        .iter()
+        .all(|(first, nested)| *nested && *first == Some(root));
+    if !shared {
+        return (None, files);
+    }
+
+    let name = root.as_os_str().to_string_lossy().to_string();
+    let files = files
+        .into_iter()
+        .map(|(path, content)| {
+            let path = path.strip_prefix(&name).unwrap_or(&path).to_path_buf();
+            (path, content)
+        })
+        .collect();
+    (Some(name), files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DbtProjectParser;
+
+    fn project() -> MemorySource {
+        MemorySource::new()
+            .with_name("shop")
+            .with_file("models/orders.sql", "select * from {{ ref('customers') }}")
+            .with_file("models/customers.sql", "select 1")
+            .with_file(
+                "models/schema.yml",
+                "models:\n  - name: orders\n    description: All orders\n",
+            )
+            .with_file("target/compiled/orders.sql", "select 1")
+            .with_file("dbt_packages/utils/models/util.sql", "select 1")
+    }
+
+    #[test]
+    fn test_parse_in_memory_project() {
+        let source = project();
+        let parsed = DbtProjectParser::new(&source).parse_project().unwrap();
+
+        assert_eq!(parsed.project.package_name(), "shop");
+        assert_eq!(parsed.project.len(), 2);
+        let orders = parsed.project.get("model.shop.orders").unwrap();
+        assert_eq!(
+            orders.as_resource().description(),
+            &Some("All orders".to_string())
+        );
+        assert_eq!(
+            orders.as_resource().depends_on(),
+            &vec!["model.shop.customers".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_tarball_source() {
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, content) in [
+            ("jaffle_shop/dbt_project.yml", "name: jaffle_shop\n"),
+            ("jaffle_shop/models/orders.sql", "select 1"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            archive
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let bytes = archive.into_inner().unwrap().finish().unwrap();
+
+        let source = TarballSource::from_reader(bytes.as_slice(), "upload.tar.gz").unwrap();
+        assert_eq!(
+            source.files().unwrap(),
+            vec![
+                PathBuf::from("dbt_project.yml"),
+                PathBuf::from("models/orders.sql")
+            ]
+        );
+        assert_eq!(source.root_name(), Some("jaffle_shop".to_string()));
+
+        let parsed = DbtProjectParser::new(&source).parse_project().unwrap();
+        assert!(parsed.project.get("model.jaffle_shop.orders").is_some());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_directory_source_skips_ignored_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["models/orders.sql", "target/run/orders.sql", ".git/HEAD"] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "select 1").unwrap();
+        }
+
+        let source = DirectorySource::new(dir.path());
+        assert_eq!(
+            source.files().unwrap(),
+            vec![PathBuf::from("models/orders.sql")]
+        );
+        assert!(matches!(
+            DirectorySource::new(dir.path().join("missing")).files(),
+            Err(DbtectiveError::ProjectNotFound { .. })
+        ));
+    }
+}
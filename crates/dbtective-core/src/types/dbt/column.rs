@@ -0,0 +1,32 @@
+use super::constraint::Constraint;
+use super::data_test::DataTest;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// A column documented in a properties (YAML) file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub description: Option<String>,
+    pub data_type: Option<String>,
+    pub tags: Vec<String>,
+    pub meta: HashMap<String, Value>,
+    pub tests: Vec<DataTest>,
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+}
+
+impl Column {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// A dbt model contract constraint, e.g. `primary_key` on a column or
+/// `unique` over several columns of a model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constraint {
+    /// `primary_key`, `not_null`, `unique`, `foreign_key` or `check`
+    pub constraint_type: String,
+    /// Columns of a model-level constraint, empty for column constraints
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+}
+
+impl Constraint {
+    pub fn new(constraint_type: impl Into<String>) -> Self {
+        Self {
+            constraint_type: constraint_type.into(),
+            columns: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::selector::glob_match;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// A generic data test applied in a properties file, e.g. `not_null` or
+/// `dbt_utils.accepted_range: {min_value: 0}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataTest {
+    /// Test name as written, with its package namespace if any
+    pub name: String,
+    /// Arguments given to the test, without its `config`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub arguments: HashMap<String, Value>,
+}
+
+impl DataTest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            arguments: HashMap::new(),
+        }
+    }
+
+    pub fn with_argument(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.arguments.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Package the test comes from, e.g. `dbt_utils`
+    pub fn namespace(&self) -> Option<&str> {
+        self.name.rsplit_once('.').map(|(namespace, _)| namespace)
+    }
+
+    /// Name without the namespace, e.g. `accepted_range`
+    pub fn test_name(&self) -> &str {
+        self.name
+            .rsplit_once('.')
+            .map_or(self.name.as_str(), |(_, name)| name)
+    }
+
+    /// Whether the test matches a name or glob. A namespaced pattern
+    /// (`dbt_utils.*`) is matched against the full name, a bare one
+    /// (`not_null`) against the name in any package.
+    pub fn matches(&self, pattern: &str) -> bool {
+        if pattern.contains('.') {
+            glob_match(pattern, &self.name)
+        } else {
+            glob_match(pattern, self.test_name())
+        }
+    }
+}
+
+/// A SQL test in the `tests` directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SingularTest {
+    pub name: String,
+    pub file_path: PathBuf,
+    /// Unique ids of the resources the test selects from
+    pub depends_on: Vec<String>,
+}
@@ -0,0 +1,109 @@
+use super::column::Column;
+use super::data_test::DataTest;
+use super::resource::{DbtResource, ResourceType};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A Jinja macro from a `{% macro %}` block, documented under `macros:` in YAML.
+/// Macros have no tests or columns; those stay empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Macro {
+    pub unique_id: String,
+    pub package_name: String,
+    pub fqn: Vec<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub tests: Vec<DataTest>,
+    pub meta: HashMap<String, Value>,
+    pub config: HashMap<String, Value>,
+    pub columns: Vec<Column>,
+    pub depends_on: Vec<String>,
+    pub sql_file: Option<PathBuf>,
+    pub yaml_file: Option<PathBuf>,
+}
+
+impl Macro {
+    pub fn new(name: String) -> Self {
+        Self {
+            unique_id: String::new(),
+            package_name: String::new(),
+            fqn: Vec::new(),
+            name,
+            description: None,
+            tags: Vec::new(),
+            tests: Vec::new(),
+            meta: HashMap::new(),
+            config: HashMap::new(),
+            columns: Vec::new(),
+            depends_on: Vec::new(),
+            sql_file: None,
+            yaml_file: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+}
+
+impl DbtResource for Macro {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn resource_type(&self) -> ResourceType {
+        ResourceType::Macro
+    }
+
+    fn description(&self) -> &Option<String> {
+        &self.description
+    }
+
+    fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    fn tests(&self) -> &Vec<DataTest> {
+        &self.tests
+    }
+
+    fn meta(&self) -> &HashMap<String, Value> {
+        &self.meta
+    }
+
+    fn config(&self) -> &HashMap<String, Value> {
+        &self.config
+    }
+
+    fn unique_id(&self) -> &str {
+        &self.unique_id
+    }
+
+    fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
+    fn fqn(&self) -> &Vec<String> {
+        &self.fqn
+    }
+
+    fn original_file_path(&self) -> Option<&Path> {
+        self.sql_file.as_deref()
+    }
+
+    fn patch_path(&self) -> Option<&Path> {
+        self.yaml_file.as_deref()
+    }
+
+    fn columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+
+    fn depends_on(&self) -> &Vec<String> {
+        &self.depends_on
+    }
+}
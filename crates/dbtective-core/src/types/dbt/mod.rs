@@ -1,7 +1,23 @@
+pub mod column;
+pub mod constraint;
+pub mod data_test;
+pub mod exposure;
+pub mod macros;
 pub mod model;
+pub mod project;
 pub mod resource;
+pub mod seed;
+pub mod snapshot;
 pub mod source;
 
+pub use column::Column;
+pub use constraint::Constraint;
+pub use data_test::{DataTest, SingularTest};
+pub use exposure::Exposure;
+pub use macros::Macro;
 pub use model::Model;
+pub use project::{DbtNode, DbtProject, NodeId};
 pub use resource::{DbtResource, ResourceType};
+pub use seed::Seed;
+pub use snapshot::Snapshot;
 pub use source::Source;
@@ -1,14 +1,28 @@
+use super::column::Column;
+use super::constraint::Constraint;
+use super::data_test::DataTest;
 use super::resource::{DbtResource, ResourceType};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
+    pub unique_id: String,
+    pub package_name: String,
+    pub fqn: Vec<String>,
     pub name: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
-    pub tests: Vec<String>,
-    pub meta: HashMap<String, String>,
+    pub tests: Vec<DataTest>,
+    pub meta: HashMap<String, Value>,
+    pub config: HashMap<String, Value>,
+    pub columns: Vec<Column>,
+    /// Model-level contract constraints
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+    pub depends_on: Vec<String>,
     pub materialized: Option<String>,
     pub sql_file: Option<PathBuf>,
     pub yaml_file: Option<PathBuf>,
@@ -16,11 +30,18 @@ pub struct Model {
 impl Model {
     pub fn new(name: String) -> Self {
         Self {
+            unique_id: String::new(),
+            package_name: String::new(),
+            fqn: Vec::new(),
             name,
             description: None,
             tags: Vec::new(),
             tests: Vec::new(),
             meta: HashMap::new(),
+            config: HashMap::new(),
+            columns: Vec::new(),
+            constraints: Vec::new(),
+            depends_on: Vec::new(),
             materialized: None,
             sql_file: None,
             yaml_file: None,
@@ -50,11 +71,47 @@ impl DbtResource for Model {
         &self.tags
     }
 
-    fn tests(&self) -> &Vec<String> {
+    fn tests(&self) -> &Vec<DataTest> {
         &self.tests
     }
 
-    fn meta(&self) -> &HashMap<String, String> {
+    fn meta(&self) -> &HashMap<String, Value> {
         &self.meta
     }
+
+    fn config(&self) -> &HashMap<String, Value> {
+        &self.config
+    }
+
+    fn unique_id(&self) -> &str {
+        &self.unique_id
+    }
+
+    fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
+    fn fqn(&self) -> &Vec<String> {
+        &self.fqn
+    }
+
+    fn original_file_path(&self) -> Option<&Path> {
+        self.sql_file.as_deref()
+    }
+
+    fn patch_path(&self) -> Option<&Path> {
+        self.yaml_file.as_deref()
+    }
+
+    fn columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+
+    fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    fn depends_on(&self) -> &Vec<String> {
+        &self.depends_on
+    }
 }
@@ -0,0 +1,384 @@
+use super::resource::{DbtResource, ResourceType};
+use super::{Exposure, Macro, Model, Seed, SingularTest, Snapshot, Source};
+use crate::types::suppression::Suppression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Index of a node in a [`DbtProject`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NodeId(usize);
+
+/// A typed dbt resource owned by a [`DbtProject`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "resource_type", rename_all = "lowercase")]
+pub enum DbtNode {
+    Model(Model),
+    Source(Source),
+    Seed(Seed),
+    Snapshot(Snapshot),
+    Exposure(Exposure),
+    Macro(Macro),
+}
+
+impl DbtNode {
+    pub fn as_resource(&self) -> &dyn DbtResource {
+        match self {
+            DbtNode::Model(model) => model,
+            DbtNode::Source(source) => source,
+            DbtNode::Seed(seed) => seed,
+            DbtNode::Snapshot(snapshot) => snapshot,
+            DbtNode::Exposure(exposure) => exposure,
+            DbtNode::Macro(macro_node) => macro_node,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.as_resource().name()
+    }
+
+    pub fn resource_type(&self) -> ResourceType {
+        self.as_resource().resource_type()
+    }
+
+    /// Fill in the package, unique id and (unless already set) the fqn of a
+    /// node added to a project
+    fn assign_identity(&mut self, project_package: &str) {
+        let resource = self.as_resource();
+        let package_name = match resource.package_name() {
+            "" => project_package.to_string(),
+            package_name => package_name.to_string(),
+        };
+
+        let name_parts = match &*self {
+            DbtNode::Source(source) => vec![source.source_name.clone(), source.name.clone()],
+            _ => vec![self.name().to_string()],
+        };
+        let unique_id = format!(
+            "{}.{}.{}",
+            self.resource_type().as_str(),
+            package_name,
+            name_parts.join(".")
+        );
+
+        let mut fqn = vec![package_name.clone()];
+        if let Some(path) = resource.original_file_path() {
+            fqn.extend(fqn_directories(path, self.resource_root()));
+        }
+        fqn.extend(name_parts);
+
+        let (node_unique_id, node_package_name, node_fqn) = match self {
+            DbtNode::Model(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+            DbtNode::Source(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+            DbtNode::Seed(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+            DbtNode::Snapshot(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+            DbtNode::Exposure(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+            DbtNode::Macro(r) => (&mut r.unique_id, &mut r.package_name, &mut r.fqn),
+        };
+        *node_unique_id = unique_id;
+        *node_package_name = package_name;
+        if node_fqn.is_empty() {
+            *node_fqn = fqn;
+        }
+    }
+
+    fn depends_on_mut(&mut self) -> &mut Vec<String> {
+        match self {
+            DbtNode::Model(model) => &mut model.depends_on,
+            DbtNode::Source(source) => &mut source.depends_on,
+            DbtNode::Seed(seed) => &mut seed.depends_on,
+            DbtNode::Snapshot(snapshot) => &mut snapshot.depends_on,
+            DbtNode::Exposure(exposure) => &mut exposure.depends_on,
+            DbtNode::Macro(macro_node) => &mut macro_node.depends_on,
+        }
+    }
+
+    /// Default project directory for the resource type (sources and
+    /// exposures are declared under models)
+    fn resource_root(&self) -> &'static str {
+        match self {
+            DbtNode::Seed(_) => "seeds",
+            DbtNode::Snapshot(_) => "snapshots",
+            DbtNode::Macro(_) => "macros",
+            DbtNode::Model(_) | DbtNode::Source(_) | DbtNode::Exposure(_) => "models",
+        }
+    }
+}
+
+/// Directories between the resource root (e.g. `models/`) and the file itself.
+/// Files outside the default root are assumed to live in a custom path one level deep.
+fn fqn_directories(path: &Path, root: &str) -> Vec<String> {
+    let directories: Vec<String> = path
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let start = directories
+        .iter()
+        .position(|directory| directory == root)
+        .map_or(1, |position| position + 1);
+    directories.into_iter().skip(start).collect()
+}
+
+/// All resources of a dbt project, stored in an arena with unique id lookup
+/// and lineage edges between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DbtProject {
+    package_name: String,
+    nodes: Vec<DbtNode>,
+    index: HashMap<String, NodeId>,
+    parents: Vec<Vec<NodeId>>,
+    children: Vec<Vec<NodeId>>,
+    /// SQL tests, which aren't nodes of the graph
+    #[serde(default)]
+    singular_tests: Vec<SingularTest>,
+    /// `dbtective: ignore` comments in the project's files
+    #[serde(default)]
+    suppressions: Vec<Suppression>,
+}
+
+impl DbtProject {
+    pub fn new(package_name: String) -> Self {
+        Self {
+            package_name,
+            ..Self::default()
+        }
+    }
+
+    pub fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
+    /// Add a node, replacing any existing node with the same unique id.
+    /// Nodes without a package are assigned the project's package.
+    pub fn add_node(&mut self, mut node: DbtNode) -> NodeId {
+        node.assign_identity(&self.package_name);
+        let unique_id = node.as_resource().unique_id().to_string();
+        if let Some(&id) = self.index.get(&unique_id) {
+            self.nodes[id.0] = node;
+            return id;
+        }
+
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(node);
+        self.index.insert(unique_id, id);
+        self.parents.push(Vec::new());
+        self.children.push(Vec::new());
+        id
+    }
+
+    /// Record that `child` depends on `parent` (via `ref()` or `source()`),
+    /// keeping the child's `depends_on` in sync with the graph
+    pub fn add_dependency(&mut self, child: NodeId, parent: NodeId) {
+        if !self.parents[child.0].contains(&parent) {
+            self.parents[child.0].push(parent);
+            self.children[parent.0].push(child);
+
+            let parent_id = self.unique_id(parent).to_string();
+            self.nodes[child.0].depends_on_mut().push(parent_id);
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &DbtNode {
+        &self.nodes[id.0]
+    }
+
+    pub fn unique_id(&self, id: NodeId) -> &str {
+        self.node(id).as_resource().unique_id()
+    }
+
+    pub fn id_of(&self, unique_id: &str) -> Option<NodeId> {
+        self.index.get(unique_id).copied()
+    }
+
+    pub fn get(&self, unique_id: &str) -> Option<&DbtNode> {
+        self.id_of(unique_id).map(|id| self.node(id))
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &DbtNode)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (NodeId(i), node))
+    }
+
+    pub fn resources(&self) -> impl Iterator<Item = &dyn DbtResource> {
+        self.nodes.iter().map(DbtNode::as_resource)
+    }
+
+    pub fn models(&self) -> impl Iterator<Item = &Model> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Model(model) => Some(model),
+            _ => None,
+        })
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &Source> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Source(source) => Some(source),
+            _ => None,
+        })
+    }
+
+    pub fn seeds(&self) -> impl Iterator<Item = &Seed> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Seed(seed) => Some(seed),
+            _ => None,
+        })
+    }
+
+    pub fn snapshots(&self) -> impl Iterator<Item = &Snapshot> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Snapshot(snapshot) => Some(snapshot),
+            _ => None,
+        })
+    }
+
+    pub fn exposures(&self) -> impl Iterator<Item = &Exposure> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Exposure(exposure) => Some(exposure),
+            _ => None,
+        })
+    }
+
+    pub fn macros(&self) -> impl Iterator<Item = &Macro> {
+        self.nodes.iter().filter_map(|node| match node {
+            DbtNode::Macro(macro_node) => Some(macro_node),
+            _ => None,
+        })
+    }
+
+    /// Direct upstream dependencies of a node
+    pub fn parents(&self, id: NodeId) -> &[NodeId] {
+        &self.parents[id.0]
+    }
+
+    /// Direct downstream dependents of a node
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.children[id.0]
+    }
+
+    pub fn add_singular_test(&mut self, test: SingularTest) {
+        self.singular_tests.push(test);
+    }
+
+    pub fn singular_tests(&self) -> &[SingularTest] {
+        &self.singular_tests
+    }
+
+    /// Singular tests selecting from the resource with this unique id
+    pub fn singular_tests_of<'p>(
+        &'p self,
+        unique_id: &'p str,
+    ) -> impl Iterator<Item = &'p SingularTest> + 'p {
+        self.singular_tests
+            .iter()
+            .filter(move |test| test.depends_on.iter().any(|id| id == unique_id))
+    }
+
+    pub fn add_suppression(&mut self, suppression: Suppression) {
+        self.suppressions.push(suppression);
+    }
+
+    /// Suppressions from comments. Those in `meta` are read from the
+    /// resources themselves, see [`Suppression::from_meta`].
+    pub fn suppressions(&self) -> &[Suppression] {
+        &self.suppressions
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arena_lookup_and_lineage() {
+        let mut project = DbtProject::new("jaffle_shop".to_string());
+        let raw = project.add_node(DbtNode::Source(Source::new(
+            "orders".to_string(),
+            "raw".to_string(),
+        )));
+        let staging = project.add_node(DbtNode::Model(Model::new("stg_orders".to_string())));
+        let seed = project.add_node(DbtNode::Seed(Seed::new("country_codes".to_string())));
+        project.add_dependency(staging, raw);
+        project.add_dependency(staging, seed);
+        project.add_dependency(staging, raw);
+
+        assert_eq!(project.len(), 3);
+        assert_eq!(project.unique_id(raw), "source.jaffle_shop.raw.orders");
+        assert_eq!(
+            project
+                .get("model.jaffle_shop.stg_orders")
+                .map(DbtNode::name),
+            Some("stg_orders")
+        );
+        assert_eq!(project.parents(staging), &[raw, seed]);
+        assert_eq!(
+            project.node(staging).as_resource().depends_on(),
+            &vec![
+                "source.jaffle_shop.raw.orders".to_string(),
+                "seed.jaffle_shop.country_codes".to_string()
+            ]
+        );
+        assert_eq!(project.children(raw), &[staging]);
+        assert_eq!(project.models().count(), 1);
+        assert_eq!(project.sources().count(), 1);
+        assert_eq!(project.seeds().count(), 1);
+        assert_eq!(project.snapshots().count(), 0);
+
+        // Re-adding a node with the same unique id replaces it in place
+        let replaced = project.add_node(DbtNode::Model(
+            Model::new("stg_orders".to_string()).with_description("Staged".to_string()),
+        ));
+        assert_eq!(replaced, staging);
+        assert_eq!(project.len(), 3);
+        assert_eq!(
+            project.node(staging).as_resource().description(),
+            &Some("Staged".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fqn_follows_directories() {
+        let mut project = DbtProject::new("jaffle_shop".to_string());
+
+        let mut model = Model::new("orders".to_string());
+        model.sql_file = Some("models/marts/finance/orders.sql".into());
+        let model = project.add_node(DbtNode::Model(model));
+
+        let mut source = Source::new("payments".to_string(), "stripe".to_string());
+        source.yaml_file = Some("models/staging/stripe/_sources.yml".into());
+        let source = project.add_node(DbtNode::Source(source));
+
+        let mut seed = Seed::new("country_codes".to_string());
+        seed.seed_file = Some("seeds/lookups/country_codes.csv".into());
+        let seed = project.add_node(DbtNode::Seed(seed));
+
+        let fqn = |id| project.node(id).as_resource().fqn().join(".");
+        assert_eq!(fqn(model), "jaffle_shop.marts.finance.orders");
+        assert_eq!(fqn(source), "jaffle_shop.staging.stripe.stripe.payments");
+        assert_eq!(fqn(seed), "jaffle_shop.lookups.country_codes");
+
+        let resource = project.node(model).as_resource();
+        assert_eq!(resource.package_name(), "jaffle_shop");
+        assert_eq!(resource.unique_id(), "model.jaffle_shop.orders");
+        assert!(resource.enabled());
+    }
+}
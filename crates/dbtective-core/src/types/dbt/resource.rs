@@ -1,6 +1,13 @@
+use super::column::Column;
+use super::constraint::Constraint;
+use super::data_test::DataTest;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ResourceType {
     Model,
     Source,
@@ -12,6 +19,7 @@ pub enum ResourceType {
 }
 
 impl ResourceType {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "model" | "models" => Some(Self::Model),
@@ -56,6 +64,130 @@ pub trait DbtResource {
     fn resource_type(&self) -> ResourceType;
     fn description(&self) -> &Option<String>;
     fn tags(&self) -> &Vec<String>;
-    fn tests(&self) -> &Vec<String>;
-    fn meta(&self) -> &HashMap<String, String>;
+    /// Generic tests applied at the resource level, column tests are on [`Column`]
+    fn tests(&self) -> &Vec<DataTest>;
+    fn meta(&self) -> &HashMap<String, Value>;
+    fn config(&self) -> &HashMap<String, Value>;
+
+    /// dbt's unique id, e.g. `model.jaffle_shop.orders` or `source.jaffle_shop.raw.payments`
+    fn unique_id(&self) -> &str;
+    fn package_name(&self) -> &str;
+    /// Fully qualified name: package, directories below the resource path, then the name
+    fn fqn(&self) -> &Vec<String>;
+    /// File defining the resource, relative to the project root
+    fn original_file_path(&self) -> Option<&Path>;
+    /// Properties (YAML) file documenting the resource, when separate from its definition
+    fn patch_path(&self) -> Option<&Path>;
+    fn columns(&self) -> &Vec<Column>;
+    /// Model-level contract constraints, column constraints are on [`Column`]
+    fn constraints(&self) -> &[Constraint] {
+        &[]
+    }
+    /// Unique ids of the resources this one selects from via `ref()` or `source()`
+    fn depends_on(&self) -> &Vec<String>;
+
+    /// Resources are enabled unless configured with `enabled: false`
+    fn enabled(&self) -> bool {
+        self.config()
+            .get("enabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(true)
+    }
+
+    /// Every property of the resource as JSON, the shape custom rules
+    /// written outside Rust receive
+    fn to_json(&self) -> serde_json::Value {
+        let path = |path: Option<&Path>| path.map(|p| p.to_string_lossy().to_string());
+        // YAML mappings with non-string keys have no JSON equivalent, so
+        // those become null rather than failing
+        let json = |value: &HashMap<String, Value>| serde_json::to_value(value).unwrap_or_default();
+        serde_json::json!({
+            "unique_id": self.unique_id(),
+            "name": self.name(),
+            "resource_type": self.resource_type().as_str(),
+            "package_name": self.package_name(),
+            "fqn": self.fqn(),
+            "original_file_path": path(self.original_file_path()),
+            "patch_path": path(self.patch_path()),
+            "description": self.description(),
+            "tags": self.tags(),
+            "tests": serde_json::to_value(self.tests()).unwrap_or_default(),
+            "meta": json(self.meta()),
+            "config": json(self.config()),
+            "columns": serde_json::to_value(self.columns()).unwrap_or_default(),
+            "depends_on": self.depends_on(),
+            "enabled": self.enabled(),
+        })
+    }
+
+    /// Look up a nested meta or config value by dotted path, e.g.
+    /// `meta.owner.email` or `config.contract.enforced`
+    fn lookup(&self, path: &str) -> Option<&Value> {
+        let (root, rest) = path.split_once('.')?;
+        match root {
+            "meta" => lookup_path(self.meta(), rest),
+            "config" => lookup_path(self.config(), rest),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve a dotted path (`owner.email`, `columns.0.name`) in a value tree.
+/// Numeric segments index into sequences.
+pub fn lookup_path<'a>(tree: &'a HashMap<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut current = tree.get(segments.next()?)?;
+
+    for segment in segments {
+        current = match current {
+            Value::Mapping(mapping) => mapping.get(segment)?,
+            Value::Sequence(items) => items.get(segment.parse::<usize>().ok()?)?,
+            Value::Tagged(tagged) => match &tagged.value {
+                Value::Mapping(mapping) => mapping.get(segment)?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+    }
+
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dbt::Model;
+
+    #[test]
+    fn test_lookup_dotted_paths() {
+        let mut model = Model::new("orders".to_string());
+        model.meta.insert(
+            "owner".to_string(),
+            serde_yaml::from_str("{team: finance, email: finance@example.com}").unwrap(),
+        );
+        model.config.insert(
+            "contract".to_string(),
+            serde_yaml::from_str("{enforced: true}").unwrap(),
+        );
+        model.config.insert(
+            "grants".to_string(),
+            serde_yaml::from_str("[reporter, analyst]").unwrap(),
+        );
+
+        assert_eq!(
+            model.lookup("meta.owner.email"),
+            Some(&Value::from("finance@example.com"))
+        );
+        assert_eq!(
+            model.lookup("config.contract.enforced"),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            model.lookup("config.grants.1"),
+            Some(&Value::from("analyst"))
+        );
+        assert_eq!(model.lookup("meta.owner.phone"), None);
+        assert_eq!(model.lookup("meta"), None);
+        assert_eq!(model.lookup("description"), None);
+    }
 }
@@ -1,27 +1,44 @@
+use super::column::Column;
+use super::data_test::DataTest;
 use super::resource::{DbtResource, ResourceType};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Source {
+    pub unique_id: String,
+    pub package_name: String,
+    pub fqn: Vec<String>,
     pub name: String,
     pub source_name: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
-    pub tests: Vec<String>,
-    pub meta: HashMap<String, String>,
-    pub yaml_file: Option<String>,
-    pub sql_file: Option<String>,
+    pub tests: Vec<DataTest>,
+    pub meta: HashMap<String, Value>,
+    pub config: HashMap<String, Value>,
+    pub columns: Vec<Column>,
+    pub depends_on: Vec<String>,
+    pub yaml_file: Option<PathBuf>,
+    pub sql_file: Option<PathBuf>,
 }
 
 impl Source {
     pub fn new(name: String, source_name: String) -> Self {
         Self {
+            unique_id: String::new(),
+            package_name: String::new(),
+            fqn: Vec::new(),
             name,
             source_name,
             description: None,
             tags: Vec::new(),
             tests: Vec::new(),
             meta: HashMap::new(),
+            config: HashMap::new(),
+            columns: Vec::new(),
+            depends_on: Vec::new(),
             yaml_file: None,
             sql_file: None,
         }
@@ -50,11 +67,43 @@ impl DbtResource for Source {
         &self.tags
     }
 
-    fn tests(&self) -> &Vec<String> {
+    fn tests(&self) -> &Vec<DataTest> {
         &self.tests
     }
 
-    fn meta(&self) -> &HashMap<String, String> {
+    fn meta(&self) -> &HashMap<String, Value> {
         &self.meta
     }
+
+    fn config(&self) -> &HashMap<String, Value> {
+        &self.config
+    }
+
+    fn unique_id(&self) -> &str {
+        &self.unique_id
+    }
+
+    fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
+    fn fqn(&self) -> &Vec<String> {
+        &self.fqn
+    }
+
+    fn original_file_path(&self) -> Option<&Path> {
+        self.yaml_file.as_deref()
+    }
+
+    fn patch_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+
+    fn depends_on(&self) -> &Vec<String> {
+        &self.depends_on
+    }
 }
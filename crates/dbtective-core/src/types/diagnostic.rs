@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// A problem found while parsing the project that isn't tied to a rule,
+/// e.g. a `var()` that can't be resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub file_path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(file_path: String, message: String) -> Self {
+        Self { file_path, message }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file_path, self.message)
+    }
+}
@@ -1,13 +1,17 @@
 use crate::rules::registry::{RuleInfo, RuleRegistry};
+use serde::{Deserialize, Serialize};
 
 use super::severity::RuleSeverity;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
     pub rule_id: String,
     pub severity: RuleSeverity,
     pub message: String,
     pub resource_name: String,
+    /// The column of the resource the finding is about, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
     pub file_path: String,
 }
 
@@ -24,10 +28,16 @@ impl Finding {
             severity,
             message,
             resource_name,
-            file_path: file_path,
+            column: None,
+            file_path,
         }
     }
 
+    pub fn with_column(mut self, column: String) -> Self {
+        self.column = Some(column);
+        self
+    }
+
     pub fn get_rule_info(&self) -> Option<&'static RuleInfo> {
         RuleRegistry::get_rule_by_str(&self.rule_id)
     }
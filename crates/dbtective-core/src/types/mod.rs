@@ -1,3 +1,5 @@
 pub mod dbt;
+pub mod diagnostic;
 pub mod finding;
 pub mod severity;
+pub mod suppression;
@@ -0,0 +1,129 @@
+use crate::rules::registry::RuleRegistry;
+use crate::types::dbt::DbtResource;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// An acknowledged exception: findings of some rules on a resource, or on
+/// one of its columns, that are not reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suppression {
+    /// Unique id of the resource the suppression is attached to
+    pub unique_id: String,
+    /// Only findings about this column
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// Rule ids or names, every rule when empty
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Where the suppression is written, e.g. `models/orders.sql:3`
+    pub location: String,
+}
+
+impl Suppression {
+    pub fn new(unique_id: String, rules: Vec<String>, location: String) -> Self {
+        Self {
+            unique_id,
+            column: None,
+            rules,
+            reason: None,
+            location,
+        }
+    }
+
+    pub fn with_column(mut self, column: String) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_reason(mut self, reason: Option<String>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    /// Suppressions in the `dbtective` entry of a resource's and its
+    /// columns' meta: `meta: {dbtective: {ignore: [DOC001], reason: "..."}}`
+    pub fn from_meta(resource: &dyn DbtResource) -> Vec<Self> {
+        let location = resource
+            .patch_path()
+            .or(resource.original_file_path())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let resource_suppression = meta_suppression(resource.meta()).map(|(rules, reason)| {
+            Self::new(
+                resource.unique_id().to_string(),
+                rules,
+                format!("{} (meta of {})", location, resource.name()),
+            )
+            .with_reason(reason)
+        });
+        let column_suppressions = resource.columns().iter().filter_map(|column| {
+            meta_suppression(&column.meta).map(|(rules, reason)| {
+                Self::new(
+                    resource.unique_id().to_string(),
+                    rules,
+                    format!("{} (meta of {}.{})", location, resource.name(), column.name),
+                )
+                .with_column(column.name.clone())
+                .with_reason(reason)
+            })
+        });
+
+        resource_suppression
+            .into_iter()
+            .chain(column_suppressions)
+            .collect()
+    }
+
+    /// Whether findings of the rule are suppressed
+    pub fn covers(&self, rule_id: &str) -> bool {
+        let name = RuleRegistry::get_rule_by_str(rule_id).map(|info| info.name);
+        self.rules.is_empty()
+            || self
+                .rules
+                .iter()
+                .any(|rule| rule == rule_id || Some(rule.as_str()) == name)
+    }
+
+    /// Whether a finding of a covered rule, about the given column if any, is
+    /// suppressed. Column suppressions only match findings about that column.
+    pub fn matches(&self, column: Option<&str>) -> bool {
+        self.column.is_none() || self.column.as_deref() == column
+    }
+}
+
+impl std::fmt::Display for Suppression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.rules.is_empty() {
+            write!(f, "{}: ignore", self.location)?;
+        } else {
+            write!(f, "{}: ignore={}", self.location, self.rules.join(","))?;
+        }
+        match &self.reason {
+            Some(reason) => write!(f, " ({})", reason),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Rules and reason of a `dbtective: {ignore: ...}` meta entry. `ignore`
+/// may be a list of rules, a single rule, or `true` for every rule.
+fn meta_suppression(meta: &HashMap<String, Value>) -> Option<(Vec<String>, Option<String>)> {
+    let entry = meta.get("dbtective")?;
+    let rules = match entry.get("ignore")? {
+        Value::Bool(true) => Vec::new(),
+        Value::String(rule) => vec![rule.clone()],
+        Value::Sequence(rules) if !rules.is_empty() => rules
+            .iter()
+            .filter_map(|rule| rule.as_str().map(str::to_string))
+            .collect(),
+        _ => return None,
+    };
+    let reason = entry
+        .get("reason")
+        .and_then(|reason| reason.as_str())
+        .map(str::to_string);
+    Some((rules, reason))
+}
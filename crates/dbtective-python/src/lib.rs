@@ -0,0 +1,410 @@
+//! Python bindings for dbtective-core.
+//!
+//! ```python
+//! import dbtective
+//!
+//! def owner(resource):
+//!     if "owner" not in resource["meta"]:
+//!         return f"{resource['name']} has no owner"
+//!
+//! dbtective.register_rule("CUSTOM_OWNER", owner, severity="error")
+//! report = dbtective.analyze("path/to/project", config={"rules": {"DOC001": {"enabled": False}}})
+//! print(report["overall_score"], report["findings"])
+//! ```
+
+use dbtective_core::config::Config;
+use dbtective_core::rules::{RuleContext, Violation};
+use dbtective_core::types::dbt::DbtResource;
+use dbtective_core::types::severity::RuleSeverity;
+use dbtective_core::{AnalyzerBuilder, DbtectiveError, Rule};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+create_exception!(
+    dbtective,
+    AnalysisError,
+    PyException,
+    "Raised when a dbt project can't be analyzed"
+);
+
+/// A rule implemented by a Python callable, called with each resource as a dict
+struct PythonRule {
+    id: String,
+    severity: Option<RuleSeverity>,
+    func: Py<PyAny>,
+}
+
+impl PythonRule {
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        Self {
+            id: self.id.clone(),
+            severity: self.severity.clone(),
+            func: self.func.clone_ref(py),
+        }
+    }
+}
+
+impl Rule for PythonRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn default_severity(&self) -> RuleSeverity {
+        self.severity.clone().unwrap_or(RuleSeverity::Warning)
+    }
+
+    fn evaluate(
+        &self,
+        resource: &dyn DbtResource,
+        _context: &RuleContext,
+    ) -> dbtective_core::error::Result<Vec<Violation>> {
+        let resource = resource.to_json();
+        Python::with_gil(|py| {
+            let resource = json_to_py(py, &resource)?;
+            messages(&self.func.bind(py).call1((resource,))?)
+        })
+        .map(|messages| messages.into_iter().map(Violation::new).collect())
+        .map_err(|error| DbtectiveError::Rule {
+            rule_id: self.id.clone(),
+            message: error.to_string(),
+        })
+    }
+}
+
+/// Rules added with `register_rule`, run by every `analyze` call
+static REGISTERED_RULES: Mutex<Vec<PythonRule>> = Mutex::new(Vec::new());
+
+/// Analyze a dbt project and return the report as a dict with `findings`,
+/// `diagnostics` and `overall_score`.
+///
+/// `config` has the shape of dbtective's configuration and defaults to the
+/// project's `dbtective.toml`, `vars` overrides
+/// project vars like dbt's `--vars`, and `rules` maps extra rule ids to callables.
+#[pyfunction]
+#[pyo3(signature = (project_dir, config=None, vars=None, rules=None, use_cache=false))]
+fn analyze<'py>(
+    py: Python<'py>,
+    project_dir: PathBuf,
+    config: Option<&Bound<'py, PyAny>>,
+    vars: Option<&Bound<'py, PyAny>>,
+    rules: Option<&Bound<'py, PyDict>>,
+    use_cache: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let config: Option<Config> = match config {
+        Some(config) => Some(
+            serde_json::from_value(py_to_json(config)?)
+                .map_err(|e| PyValueError::new_err(format!("Invalid config: {}", e)))?,
+        ),
+        None => None,
+    };
+    let vars: HashMap<String, serde_yaml::Value> = match vars {
+        Some(vars) => serde_json::from_value(py_to_json(vars)?)
+            .map_err(|e| PyValueError::new_err(format!("vars must be a dict: {}", e)))?,
+        None => HashMap::new(),
+    };
+
+    let mut builder = AnalyzerBuilder::new(project_dir)
+        .with_cache(use_cache)
+        .with_vars(vars);
+    if let Some(config) = config {
+        builder = builder.with_config(config);
+    }
+    for rule in registered_rules(py) {
+        builder = builder.with_rule(rule);
+    }
+    if let Some(rules) = rules {
+        for (id, func) in rules {
+            builder = builder.with_rule(python_rule(id.extract()?, func, None)?);
+        }
+    }
+
+    // Parsing doesn't need the GIL; Python rules take it back while they run
+    let analyzer = builder.build();
+    let report = py
+        .allow_threads(|| analyzer.analyze())
+        .map_err(|e| AnalysisError::new_err(e.to_string()))?;
+
+    let report =
+        serde_json::to_value(&report).map_err(|e| AnalysisError::new_err(e.to_string()))?;
+    json_to_py(py, &report)
+}
+
+/// Register a rule for all following `analyze` calls. `func` receives a
+/// resource dict and returns None when it passes, or a message (or list of
+/// messages) describing what is wrong.
+#[pyfunction]
+#[pyo3(signature = (rule_id, func, severity=None))]
+fn register_rule(rule_id: String, func: &Bound<'_, PyAny>, severity: Option<&str>) -> PyResult<()> {
+    let severity = severity
+        .map(|severity| {
+            RuleSeverity::from_str(severity).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Invalid severity '{}', expected error, warning or info",
+                    severity
+                ))
+            })
+        })
+        .transpose()?;
+
+    let rule = python_rule(rule_id, func.clone(), severity)?;
+    let mut rules = REGISTERED_RULES.lock().unwrap_or_else(|e| e.into_inner());
+    rules.retain(|registered| registered.id != rule.id);
+    rules.push(rule);
+    Ok(())
+}
+
+/// Remove every rule added with `register_rule`
+#[pyfunction]
+fn clear_rules() {
+    REGISTERED_RULES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+fn python_rule(
+    id: String,
+    func: Bound<'_, PyAny>,
+    severity: Option<RuleSeverity>,
+) -> PyResult<PythonRule> {
+    if !func.is_callable() {
+        return Err(PyTypeError::new_err(format!(
+            "Rule '{}' must be callable",
+            id
+        )));
+    }
+
+    Ok(PythonRule {
+        id,
+        severity,
+        func: func.unbind(),
+    })
+}
+
+fn registered_rules(py: Python<'_>) -> Vec<PythonRule> {
+    REGISTERED_RULES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|rule| rule.clone_ref(py))
+        .collect()
+}
+
+/// A rule callable returns None, a message, or an iterable of messages
+fn messages(result: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    if result.is_none() {
+        return Ok(Vec::new());
+    }
+    if let Ok(message) = result.extract::<String>() {
+        return Ok(vec![message]);
+    }
+
+    result
+        .try_iter()?
+        .map(|item| item?.extract::<String>())
+        .collect()
+}
+
+fn json_to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(value) => PyBool::new(py, *value).to_owned().into_any(),
+        Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(value), _) => value.into_pyobject(py)?.into_any(),
+            (_, Some(value)) => value.into_pyobject(py)?.into_any(),
+            _ => PyFloat::new(py, number.as_f64().unwrap_or(f64::NAN)).into_any(),
+        },
+        Value::String(value) => PyString::new(py, value).into_any(),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| json_to_py(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, json_to_py(py, value)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = value.downcast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if let Ok(value) = value.downcast::<PyInt>() {
+        Ok(Value::from(value.extract::<i64>()?))
+    } else if let Ok(value) = value.downcast::<PyFloat>() {
+        Ok(serde_json::Number::from_f64(value.value()).map_or(Value::Null, Value::Number))
+    } else if let Ok(value) = value.downcast::<PyString>() {
+        Ok(Value::String(value.to_string()))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut map = serde_json::Map::new();
+        for (key, value) in dict {
+            map.insert(key.str()?.to_string(), py_to_json(&value)?);
+        }
+        Ok(Value::Object(map))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        list.iter().map(|item| py_to_json(&item)).collect()
+    } else if let Ok(tuple) = value.downcast::<PyTuple>() {
+        tuple.iter().map(|item| py_to_json(&item)).collect()
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Unsupported value of type {}",
+            value.get_type().name()?
+        )))
+    }
+}
+
+#[pymodule]
+fn dbtective(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    m.add_function(wrap_pyfunction!(register_rule, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_rules, m)?)?;
+    m.add("AnalysisError", m.py().get_type::<AnalysisError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn project_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("models")).unwrap();
+        std::fs::write(
+            dir.path().join("models/orders.sql"),
+            "{{ config(meta={'owner': 'finance'}) }} select 1",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("models/customers.sql"), "select 1").unwrap();
+        dir
+    }
+
+    fn eval<'py>(py: Python<'py>, code: &str) -> Bound<'py, PyAny> {
+        py.eval(&CString::new(code).unwrap(), None, None).unwrap()
+    }
+
+    #[test]
+    fn test_analyze_with_python_rule() {
+        let dir = project_dir();
+        Python::with_gil(|py| {
+            let rules = PyDict::new(py);
+            rules
+                .set_item(
+                    "CUSTOM_OWNER",
+                    eval(
+                        py,
+                        "lambda r: None if 'owner' in r['meta'] else r['name'] + ' has no owner'",
+                    ),
+                )
+                .unwrap();
+            let config = eval(
+                py,
+                "{'rules': {'CUSTOM_OWNER': {'severity': 'error'}, 'DOC001': {'enabled': False}, 'TEST001': {'enabled': False}, 'TEST002': {'enabled': False}, 'TEST003': {'enabled': False}}}",
+            );
+
+            let report = analyze(
+                py,
+                dir.path().to_path_buf(),
+                Some(&config),
+                None,
+                Some(&rules),
+                false,
+            )
+            .unwrap();
+
+            let findings = report.get_item("findings").unwrap();
+            assert_eq!(findings.len().unwrap(), 1);
+            let finding = findings.get_item(0).unwrap();
+            assert_eq!(
+                finding
+                    .get_item("message")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "customers has no owner"
+            );
+            assert_eq!(
+                finding
+                    .get_item("severity")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "error"
+            );
+            assert_eq!(
+                report
+                    .get_item("overall_score")
+                    .unwrap()
+                    .extract::<f64>()
+                    .unwrap(),
+                50.0
+            );
+        });
+    }
+
+    #[test]
+    fn test_failing_python_rule_raises() {
+        let dir = project_dir();
+        Python::with_gil(|py| {
+            let rules = PyDict::new(py);
+            rules
+                .set_item("BROKEN", eval(py, "lambda r: 1 / 0"))
+                .unwrap();
+
+            let error = analyze(
+                py,
+                dir.path().to_path_buf(),
+                None,
+                None,
+                Some(&rules),
+                false,
+            )
+            .unwrap_err();
+            assert!(error.is_instance_of::<AnalysisError>(py));
+            assert!(error.to_string().contains("ZeroDivisionError"));
+        });
+    }
+
+    #[test]
+    fn test_project_config_is_used_without_config() {
+        let dir = project_dir();
+        std::fs::write(
+            dir.path().join("dbtective.toml"),
+            "[rules.DOC001]\nenabled = false\n",
+        )
+        .unwrap();
+        Python::with_gil(|py| {
+            let report = analyze(py, dir.path().to_path_buf(), None, None, None, false).unwrap();
+
+            let findings = report.get_item("findings").unwrap();
+            let rule_ids: Vec<String> = findings
+                .try_iter()
+                .unwrap()
+                .map(|finding| {
+                    finding
+                        .unwrap()
+                        .get_item("rule_id")
+                        .unwrap()
+                        .extract()
+                        .unwrap()
+                })
+                .collect();
+            assert!(!rule_ids.is_empty());
+            assert!(!rule_ids.iter().any(|id| id == "DOC001"));
+        });
+    }
+}
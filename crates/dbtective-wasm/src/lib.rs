@@ -0,0 +1,101 @@
+//! WebAssembly bindings for dbtective-core, for linting in the browser.
+//!
+//! Build with `wasm-pack build crates/dbtective-wasm --target web`, then:
+//!
+//! ```js
+//! import init, { analyze } from "./pkg/dbtective_wasm.js";
+//!
+//! await init();
+//! const report = JSON.parse(analyze({
+//!   "models/orders.sql": "select * from {{ ref('customers') }}",
+//!   "models/schema.yml": "models:\n  - name: orders\n",
+//! }));
+//! console.log(report.findings);
+//! ```
+
+use dbtective_core::config::Config;
+use dbtective_core::error::Result;
+use dbtective_core::{AnalysisReport, AnalyzerBuilder, MemorySource};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+/// Analyze a project given as an object (or `Map`) of file paths to contents,
+/// relative to the project root. `config` has the shape of dbtective's
+/// configuration and may be left out.
+///
+/// Returns the report as JSON, with `findings`, `diagnostics` and `overall_score`.
+#[wasm_bindgen]
+pub fn analyze(files: JsValue, config: JsValue) -> std::result::Result<String, JsError> {
+    let files: BTreeMap<String, String> = serde_wasm_bindgen::from_value(files)
+        .map_err(|e| JsError::new(&format!("files must map paths to contents: {}", e)))?;
+    let config: Option<Config> = serde_wasm_bindgen::from_value(config)
+        .map_err(|e| JsError::new(&format!("Invalid config: {}", e)))?;
+
+    let report = analyze_files(files, config).map_err(|e| JsError::new(&e.to_string()))?;
+    serde_json::to_string(&report).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// The dbtective version the module was built from
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Without a config, a `dbtective.toml` among the files is used if there is one
+fn analyze_files(
+    files: BTreeMap<String, String>,
+    config: Option<Config>,
+) -> Result<AnalysisReport> {
+    let source: MemorySource = files.into_iter().collect();
+    let mut builder = AnalyzerBuilder::from_source(source);
+    if let Some(config) = config {
+        builder = builder.with_config(config);
+    }
+    builder.build().analyze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dbtective_core::DbtectiveError;
+
+    fn files(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_analyze_pasted_files() {
+        let report = analyze_files(
+            files(&[
+                ("dbt_project.yml", "name: shop\n"),
+                ("models/orders.sql", "select * from {{ ref('customers') }}"),
+                ("models/customers.sql", "select 1"),
+                (
+                    "models/schema.yml",
+                    "models:\n  - name: orders\n    description: Orders\n",
+                ),
+            ]),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(report.resource_count, 2);
+        assert_eq!(report.project_path, "in-memory project");
+        assert!(report.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_yaml_is_an_error() {
+        let error = analyze_files(
+            files(&[("models/schema.yml", "models:\n  - name: [unclosed\n")]),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, DbtectiveError::Yaml { .. }));
+        assert!(error.to_string().starts_with("models/schema.yml:"));
+    }
+}